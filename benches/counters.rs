@@ -0,0 +1,161 @@
+//! Compares [`Counter`], which runs its work on Tokio's blocking pool, with
+//! [`AsyncCounter`], which sleeps on the async workers, with a hundred
+//! increments in flight at once.
+//!
+//! The blocking counter ties up a pool thread per task, so its thread count
+//! grows with the load; the async counter's doesn't. Wall time is close, with
+//! the async counter slightly behind since Tokio's timer rounds each of a
+//! task's sleeps up to the next millisecond. Saving threads is only a win when
+//! the work really is waiting: CPU-bound work belongs on the blocking pool,
+//! where it can't stall the runtime.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use ractor::{Actor, ActorProcessingErr, ActorRef, cast};
+use ratatui_ractor::{AppMessage, AsyncCounter, Counter, CounterArgs, CounterMessage};
+use tokio::{runtime::Runtime, sync::oneshot};
+
+const TASKS: usize = 100;
+const WORK_DURATION: Duration = Duration::from_millis(100);
+
+/// Stands in for the app, collecting finished values until it has them all.
+struct Sink;
+
+struct SinkState {
+    values: Vec<u8>,
+    done: Option<oneshot::Sender<Vec<u8>>>,
+}
+
+impl Actor for Sink {
+    type Msg = AppMessage;
+
+    type State = SinkState;
+
+    type Arguments = oneshot::Sender<Vec<u8>>;
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        done: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(SinkState {
+            values: Vec::with_capacity(TASKS),
+            done: Some(done),
+        })
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        if let AppMessage::UpdateCountWithMetadata { value, .. } = message {
+            state.values.push(value);
+            if state.values.len() == TASKS
+                && let Some(done) = state.done.take()
+            {
+                let _ = done.send(std::mem::take(&mut state.values));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs one increment on each of [`TASKS`] counters and returns the
+/// resulting values, sorted.
+async fn run<A>(counter: fn() -> A) -> Vec<u8>
+where
+    A: Actor<Msg = CounterMessage, Arguments = CounterArgs>,
+{
+    let (send, recv) = oneshot::channel();
+    let (sink, sink_handle) = Actor::spawn(None, Sink, send).await.unwrap();
+    let mut counters = Vec::with_capacity(TASKS);
+    for i in 0..TASKS {
+        let args = CounterArgs {
+            work_duration: WORK_DURATION,
+            app: Some(sink.clone()),
+            ..CounterArgs::default()
+        };
+        let (actor, handle) = Actor::spawn(None, counter(), args).await.unwrap();
+        cast!(actor, CounterMessage::Increment { from: i as u8 }).unwrap();
+        counters.push((actor, handle));
+    }
+    let mut values = recv.await.unwrap();
+    for (actor, handle) in counters {
+        actor.stop(None);
+        handle.await.unwrap();
+    }
+    sink.stop(None);
+    sink_handle.await.unwrap();
+    values.sort_unstable();
+    values
+}
+
+/// The process's current thread count, where the platform reports it.
+fn thread_count() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Runs once on a fresh runtime, sampling the thread count throughout, and
+/// returns the values along with the peak count.
+fn run_sampled<A>(counter: fn() -> A) -> (Vec<u8>, Option<usize>)
+where
+    A: Actor<Msg = CounterMessage, Arguments = CounterArgs>,
+{
+    let running = Arc::new(AtomicBool::new(true));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let sampler = {
+        let running = running.clone();
+        let peak = peak.clone();
+        std::thread::spawn(move || {
+            while running.load(Ordering::Relaxed) {
+                if let Some(threads) = thread_count() {
+                    peak.fetch_max(threads, Ordering::Relaxed);
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        })
+    };
+    let values = Runtime::new().unwrap().block_on(run(counter));
+    running.store(false, Ordering::Relaxed);
+    sampler.join().unwrap();
+    let peak = Some(peak.load(Ordering::Relaxed)).filter(|&peak| peak > 0);
+    (values, peak)
+}
+
+fn counters(c: &mut Criterion) {
+    // Async first, so the blocking pool's idle threads can't inflate its count.
+    let (async_values, async_peak) = run_sampled(|| AsyncCounter);
+    let (blocking_values, blocking_peak) = run_sampled(|| Counter);
+    assert_eq!(
+        blocking_values, async_values,
+        "counters disagree for the same inputs"
+    );
+    eprintln!("peak threads: Counter {blocking_peak:?}, AsyncCounter {async_peak:?}");
+
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("100 concurrent increments");
+    group.sample_size(10);
+    group.bench_function("Counter", |b| b.to_async(&rt).iter(|| run(|| Counter)));
+    group.bench_function("AsyncCounter", |b| {
+        b.to_async(&rt).iter(|| run(|| AsyncCounter))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, counters);
+criterion_main!(benches);
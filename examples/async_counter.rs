@@ -0,0 +1,91 @@
+//! Drives an `AsyncCounter`, whose simulated work sleeps on the runtime's
+//! workers rather than holding a blocking-pool thread.
+//!
+//! Ten increments are started at once, each on its own counter, with a short
+//! work duration. They all finish about one work duration later without the
+//! process gaining a thread per task.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use ractor::{Actor, ActorProcessingErr, ActorRef, cast};
+use ratatui_ractor::{AppMessage, AsyncCounter, CounterArgs, CounterMessage};
+use tokio::sync::oneshot;
+
+const COUNTERS: u8 = 10;
+
+/// Stands in for the app, printing each value until every counter is done.
+struct Printer;
+
+struct PrinterState {
+    remaining: u8,
+    done: Option<oneshot::Sender<()>>,
+}
+
+impl Actor for Printer {
+    type Msg = AppMessage;
+
+    type State = PrinterState;
+
+    type Arguments = oneshot::Sender<()>;
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        done: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(PrinterState {
+            remaining: COUNTERS,
+            done: Some(done),
+        })
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        if let AppMessage::UpdateCountWithMetadata { value, .. } = message {
+            println!("{}", value);
+            state.remaining -= 1;
+            if state.remaining == 0
+                && let Some(done) = state.done.take()
+            {
+                let _ = done.send(());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let (send, recv) = oneshot::channel();
+    let (printer, printer_handle) = Actor::spawn(None, Printer, send).await?;
+
+    let mut counters = Vec::new();
+    for i in 0..COUNTERS {
+        let (counter, counter_handle) = Actor::spawn(
+            None,
+            AsyncCounter,
+            CounterArgs {
+                work_duration: Duration::from_millis(500),
+                app: Some(printer.clone()),
+                ..CounterArgs::default()
+            },
+        )
+        .await?;
+        cast!(counter, CounterMessage::Increment { from: i * 10 })?;
+        counters.push((counter, counter_handle));
+    }
+
+    recv.await?;
+    for (counter, counter_handle) in counters {
+        counter.stop(None);
+        counter_handle.await?;
+    }
+    printer.stop(None);
+    printer_handle.await?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Drives a `Counter` whose transform steps through the Fibonacci sequence.
+//!
+//! Instead of the TUI, a small actor standing in for the app prints each value
+//! and immediately requests the next increment. Every step runs the counter's
+//! full simulated workload, so expect this to take a little while.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use ractor::{Actor, ActorProcessingErr, ActorRef, cast};
+use ratatui_ractor::{AppMessage, Counter, CounterArgs, CounterMessage, names::COUNTER_ACTOR_NAME};
+
+const STEPS: usize = 6;
+
+/// The smallest Fibonacci number greater than `x`, saturating at `u8::MAX`.
+fn next_fibonacci(x: u8) -> u8 {
+    let (mut a, mut b) = (0u8, 1u8);
+    while a <= x && a < u8::MAX {
+        (a, b) = (b, a.saturating_add(b));
+    }
+    a
+}
+
+struct Printer;
+
+struct PrinterArgs {
+    counter: ActorRef<CounterMessage>,
+}
+
+struct PrinterState {
+    counter: ActorRef<CounterMessage>,
+    remaining: usize,
+}
+
+impl Actor for Printer {
+    type Msg = AppMessage;
+
+    type State = PrinterState;
+
+    type Arguments = PrinterArgs;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        cast!(args.counter, CounterMessage::AppReady(myself))?;
+        cast!(args.counter, CounterMessage::Increment { from: 0 })?;
+        Ok(PrinterState {
+            counter: args.counter,
+            remaining: STEPS,
+        })
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        if let AppMessage::UpdateCountWithMetadata { value, .. } = message {
+            println!("{}", value);
+            state.remaining -= 1;
+            if state.remaining == 0 {
+                myself.stop(None);
+            } else {
+                cast!(state.counter, CounterMessage::Increment { from: value })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let (counter, counter_handle) = Actor::spawn(
+        Some(COUNTER_ACTOR_NAME.to_string()),
+        Counter,
+        CounterArgs {
+            transform: Arc::new(next_fibonacci),
+            ..CounterArgs::default()
+        },
+    )
+    .await?;
+    let (_printer, printer_handle) = Actor::spawn(
+        None,
+        Printer,
+        PrinterArgs {
+            counter: counter.clone(),
+        },
+    )
+    .await?;
+
+    printer_handle.await?;
+    counter.stop(None);
+    counter_handle.await?;
+    Ok(())
+}
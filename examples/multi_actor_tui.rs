@@ -0,0 +1,216 @@
+//! Three independent counters side by side, each as a gauge, with a status
+//! line naming whichever changed last.
+//!
+//! `c` and `m` step the "cpu" and "memory" counters; "requests" counts up on
+//! its own once a second. `q` quits.
+//!
+//! Rather than the crate's `App`, a small dashboard actor receives the
+//! counters' updates, which is all a counter needs from whatever shows it.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ractor::{Actor, ActorProcessingErr, ActorRef};
+use ratatui::{
+    DefaultTerminal,
+    layout::{Constraint, Layout},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Gauge},
+};
+use ratatui_ractor::{
+    AppMessage, Counter, CounterArgs, CounterHandle, Ticker, TickerArgs,
+    events::EventStreamActor,
+    terminal::{self, TerminalMode},
+};
+
+/// Each counter's name, the most it counts to, and how far a step goes.
+const COUNTERS: [(&str, u8, u8); 3] = [("cpu", 100, 5), ("memory", 64, 2), ("requests", 255, 1)];
+
+/// The counter the ticker steps, standing in for traffic arriving on its own.
+const AUTOMATIC: &str = "requests";
+
+/// Short enough that a step lands well before the next tick; a counter
+/// cancels a running step when asked for another.
+const WORK_DURATION: Duration = Duration::from_millis(300);
+
+struct Column {
+    name: &'static str,
+    max: u8,
+    value: u8,
+    handle: CounterHandle,
+}
+
+struct Dashboard;
+
+struct DashboardState {
+    tui: DefaultTerminal,
+    columns: Vec<Column>,
+    // Shown in the status line; `None` until the first update.
+    last_active: Option<&'static str>,
+}
+
+impl DashboardState {
+    fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Asks a counter for its next step. The value it steps from is the
+    /// dashboard's, which is the latest the counter reported.
+    async fn step(&self, name: &str) -> Result<()> {
+        if let Some(column) = self.column(name) {
+            column
+                .handle
+                .increment_with_metadata(column.value, "step".to_string(), "dashboard".to_string())
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        self.tui.draw(|frame| {
+            let [columns_area, status_area] =
+                Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+            let areas = Layout::horizontal([Constraint::Ratio(1, 3); 3]).split(columns_area);
+            for (column, &area) in self.columns.iter().zip(areas.iter()) {
+                let gauge = Gauge::default()
+                    .block(Block::bordered().title(format!(" {} ", column.name)))
+                    .ratio(f64::from(column.value) / f64::from(column.max))
+                    .label(format!("{}/{}", column.value, column.max))
+                    .yellow();
+                frame.render_widget(gauge, area);
+            }
+            let status = match self.last_active {
+                Some(name) => Line::from(vec![" Last active: ".dim(), name.bold()]),
+                None => Line::from(" Waiting for updates".dim()),
+            };
+            frame.render_widget(status, status_area);
+        })?;
+        Ok(())
+    }
+}
+
+impl Actor for Dashboard {
+    // Speaking the app's protocol is what lets the counters and the ticker
+    // talk to this actor unchanged.
+    type Msg = AppMessage;
+
+    type State = DashboardState;
+
+    type Arguments = (DefaultTerminal, Vec<Column>);
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        (tui, columns): Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(DashboardState {
+            tui,
+            columns,
+            last_active: None,
+        })
+    }
+
+    async fn post_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        // Counters hold their updates until told where to send them, which
+        // is here now we're up.
+        for column in &state.columns {
+            column.handle.app_ready(myself.clone()).await?;
+        }
+        state.draw()?;
+        Ok(())
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        match message {
+            // Named counters report by name, which is how the dashboard tells
+            // them apart.
+            AppMessage::UpdateNamedCount(name, value) => {
+                if let Some(column) = state.columns.iter_mut().find(|c| c.name == name) {
+                    column.value = value;
+                    state.last_active = Some(column.name);
+                }
+                state.draw()?;
+            }
+            AppMessage::Tick => state.step(AUTOMATIC).await?,
+            AppMessage::HandleEvent(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                match key.code {
+                    KeyCode::Char('c') => state.step("cpu").await?,
+                    KeyCode::Char('m') => state.step("memory").await?,
+                    KeyCode::Char('q') => myself.stop(None),
+                    _ => {}
+                }
+            }
+            AppMessage::HandleEvent(Event::Resize(..)) => state.draw()?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mode = TerminalMode::default();
+    let tui = terminal::init(mode)?;
+
+    // One actor per counter, so a slow step on one never holds up another.
+    // Each gets its own bounds and step size through its transform.
+    let mut columns = Vec::new();
+    let mut counters = Vec::new();
+    for (name, max, step) in COUNTERS {
+        let (counter, counter_handle) = Actor::spawn(
+            Some(name.to_string()),
+            Counter,
+            CounterArgs {
+                transform: Arc::new(move |x: u8| x.saturating_add(step).min(max)),
+                work_duration: WORK_DURATION,
+                name: Some(name.to_string()),
+                ..CounterArgs::default()
+            },
+        )
+        .await?;
+        columns.push(Column {
+            name,
+            max,
+            value: 0,
+            handle: CounterHandle::new(counter.clone()),
+        });
+        counters.push((counter, counter_handle));
+    }
+
+    let (dashboard, dashboard_handle) = Actor::spawn(None, Dashboard, (tui, columns)).await?;
+    // The ticker is what drives "requests" without any input.
+    let (ticker, ticker_handle) = Actor::spawn(
+        None,
+        Ticker,
+        TickerArgs {
+            app: dashboard.clone(),
+            interval: Duration::from_secs(1),
+        },
+    )
+    .await?;
+    let (events, events_handle) = Actor::spawn(None, EventStreamActor, dashboard).await?;
+
+    // The dashboard stops itself on `q`; everything else follows it down.
+    let stopped = dashboard_handle.await;
+    events.stop(None);
+    ticker.stop(None);
+    events_handle.await?;
+    ticker_handle.await?;
+    for (counter, counter_handle) in counters {
+        counter.stop(None);
+        counter_handle.await?;
+    }
+    terminal::restore(mode);
+    Ok(stopped?)
+}
@@ -1,33 +1,728 @@
-use std::{io::Stdout, sync::Arc};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ractor::{Actor, ActorRef, RpcReplyPort, cast};
 use ratatui::{
-    Frame, Terminal,
+    Frame,
     buffer::Buffer,
-    layout::Rect,
-    prelude::CrosstermBackend,
-    style::Stylize,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
     symbols::border,
     text::{Line, Text},
-    widgets::{Block, Paragraph, Widget},
+    widgets::{Block, Clear, List, Paragraph, Sparkline, Widget},
 };
-use tokio::sync::Mutex;
+use tokio::sync::watch;
+use tracing::Instrument;
+use unicode_width::UnicodeWidthStr;
 
-use crate::counter::CounterMessage;
+use crate::{
+    audit::{AuditEntry, AuditMessage},
+    call_timeout,
+    capabilities::Capabilities,
+    counter::{CounterHandle, CounterMessage},
+    demo::Demo,
+    diagnostics::{FrameTimings, MessageCounts},
+    memory_pressure::MemoryPressureLevel,
+    mirror::broadcast_count,
+    modal::{Modal, ModalAction},
+    names::{APP_ACTOR_NAME, AUDIT_ACTOR_NAME, namespaced},
+    palette::{Palette, PaletteAction},
+    panel::{Panel, PanelId},
+    replay::{ReplayEvent, ReplayLog, ReplayOp},
+    script::ScriptCommand,
+    strings::Strings,
+    terminal::DrawTarget,
+    theme::Theme,
+    toast::{DEFAULT_TOAST_DURATION, ToastQueue},
+};
 
 pub struct App;
 
+/// The app's setup didn't finish within its time limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartupTimeoutError {
+    pub after: Duration,
+}
+
+impl std::fmt::Display for StartupTimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "app setup timed out after {:?}", self.after)
+    }
+}
+
+impl std::error::Error for StartupTimeoutError {}
+
+/// The app was given something it can't start with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppStartupError {
+    /// The terminal can't report its size, or has no room, so nothing could
+    /// be drawn to it.
+    InvalidTerminal,
+    InvalidConfig(String),
+    /// The counter the app was given has already stopped.
+    MissingCounterRef,
+}
+
+impl std::fmt::Display for AppStartupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppStartupError::InvalidTerminal => write!(f, "the terminal can't be drawn to"),
+            AppStartupError::InvalidConfig(reason) => write!(f, "bad app config: {}", reason),
+            AppStartupError::MissingCounterRef => write!(f, "the counter isn't running"),
+        }
+    }
+}
+
+impl std::error::Error for AppStartupError {}
+
+/// How far back the increments-per-second rate looks.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Draws slower than this are logged as frame budget overruns.
+const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+/// How long the app's setup may take before its spawn fails, unless
+/// configured otherwise.
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long after a draw further changes wait to be drawn together, unless
+/// configured otherwise; about one frame at 60 FPS.
+pub const DEFAULT_REDRAW_WINDOW: Duration = Duration::from_millis(16);
+
+/// How long increments are gathered before going to the counter as one
+/// `IncrementBy`, so a burst of presses makes one task rather than many.
+const INCREMENT_WINDOW: Duration = Duration::from_millis(50);
+
+/// Resets from above this value ask first, unless configured otherwise.
+pub const DEFAULT_RESET_CONFIRM_ABOVE: u8 = 50;
+
+/// Bounds for adjusting the counter's work duration from the keyboard.
+const MIN_WORK_DURATION: Duration = Duration::from_millis(100);
+const MAX_WORK_DURATION: Duration = Duration::from_secs(60);
+
+/// How many recent updates the log panel keeps.
+const LOG_CAPACITY: usize = 100;
+
+/// Right presses closer together than this count as the key being held.
+const HOLD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The increment step once Right has been held for at least the given time.
+const ACCELERATION: [(Duration, u8); 4] = [
+    (Duration::ZERO, 1),
+    (Duration::from_secs(1), 2),
+    (Duration::from_secs(2), 5),
+    (Duration::from_secs(3), 10),
+];
+
+/// How long the toast for a key that has nothing to do stays up.
+const FLASH_DURATION: Duration = Duration::from_secs(1);
+
+/// How many recent values the sparkline shows.
+const HISTORY_CAPACITY: usize = 60;
+
+/// How many ticks a jump of more than one takes to count up or down to.
+const ANIMATION_FRAMES: u8 = 4;
+
+/// Frames of the pending-increment spinner, advanced on each progress update.
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
 pub struct AppArgs {
-    pub tui: Terminal<CrosstermBackend<Stdout>>,
+    /// Usually a boxed [`ratatui::DefaultTerminal`].
+    pub tui: Box<dyn DrawTarget>,
+    /// Disables the spinner and blinking; progress is shown as a static
+    /// percentage instead.
+    pub reduce_motion: bool,
+    pub counter: CounterHandle,
+    /// The counter's work duration at startup, adjusted from the keyboard.
+    pub work_duration: Duration,
+    /// Graphs recent values under the counter.
+    pub show_sparkline: bool,
+    /// Where the counter and its details sit in the counter panel.
+    pub align: VerticalAlign,
+    /// Plays the [`Demo`] and exits.
+    pub demo: bool,
+    /// Changes within this long of the last draw are held and drawn together
+    /// once it's passed, trading latency for fewer draws. Zero draws after
+    /// every change.
+    pub redraw_window: Duration,
+    /// How long checking the terminal and setting up may take before the
+    /// spawn fails with a [`StartupTimeoutError`].
+    pub startup_timeout: Duration,
+    /// Draws borders with `+`, `-` and `|` for terminals without box-drawing
+    /// glyphs.
+    pub ascii_borders: bool,
+    /// Colors are adjusted to what the terminal can show.
+    pub theme: Theme,
+    pub strings: Strings,
+    pub capabilities: Capabilities,
+    /// Resets from above this value ask for confirmation first.
+    pub reset_confirm_above: u8,
+    /// Increments and decrements that would cancel a task at least this
+    /// many percent done ask first. `None` never asks.
+    pub cancel_confirm_above: Option<u8>,
+    /// Prefixes the names of the actors the app finds by name, as
+    /// [`crate::names::namespaced`] does. The app should be registered under
+    /// the same namespace.
+    pub namespace: Option<String>,
+}
+
+/// Where in the counter panel its text goes. Any sparkline fills the space
+/// left over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+impl std::str::FromStr for VerticalAlign {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(VerticalAlign::Top),
+            "center" => Ok(VerticalAlign::Center),
+            "bottom" => Ok(VerticalAlign::Bottom),
+            _ => Err(format!("unknown alignment {:?}", s)),
+        }
+    }
+}
+
+/// Why rendering is paused. Only a user-requested pause shows an overlay;
+/// when focus is lost the terminal isn't visible, so nothing is drawn at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum PauseReason {
+    FocusLost,
+    UserRequested,
+}
+
+/// A counter registered with [`AppMessage::RegisterCounter`].
+#[derive(Debug)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
+struct NamedCounter {
+    name: String,
+    value: u8,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    handle: CounterHandle,
+}
+
+/// The lowest, highest and mean value over the session, kept up to date
+/// with each change rather than recomputed from the history.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
+struct ValueStats {
+    min: u8,
+    max: u8,
+    sum: u64,
+    count: u64,
+}
+
+impl ValueStats {
+    fn record(&mut self, value: u8) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.sum += u64::from(value);
+        self.count += 1;
+    }
+
+    /// `None` until there's been a value.
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+    }
+}
+
+/// What keys currently do. Each mode has its own bottom instruction line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayMode {
+    Normal,
+    /// Typing a value to set the counter to.
+    Input(String),
+    /// Typing a value to be told when the counter reaches; left empty, it
+    /// stops watching.
+    WatchInput(String),
+    /// Showing every key binding.
+    Help,
+    /// Showing a dialog, which gets every key until it's closed.
+    Modal(Modal),
+    /// Picking an action from the command palette.
+    Palette(Palette),
+}
+
+/// Which way the up and down keys move through a panel's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scroll {
+    Older,
+    Newer,
+}
+
+/// A key binding in normal mode.
+struct KeyBinding {
+    key: &'static str,
+    /// What the key does, as listed in the help overlay.
+    description: &'static str,
+    /// The binding's name in the instructions line, if it's shown there.
+    instruction: Option<&'static str>,
+}
+
+impl KeyBinding {
+    const fn new(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            instruction: None,
+        }
+    }
+
+    const fn shown_as(self, instruction: &'static str) -> Self {
+        Self {
+            instruction: Some(instruction),
+            ..self
+        }
+    }
+}
+
+/// Every key binding in normal mode, as listed in the help overlay and, for
+/// those with an instruction, the instructions line.
+const KEY_BINDINGS: [KeyBinding; 20] = [
+    KeyBinding::new("<Tab>", "Focus the next counter"),
+    KeyBinding::new("<S-Tab>", "Focus the next panel"),
+    KeyBinding::new("<Up/Dn>", "Scroll the focused panel"),
+    KeyBinding::new("<Left>", "Decrement").shown_as("Decrement"),
+    KeyBinding::new("<Right>", "Increment (hold to speed up)").shown_as("Increment"),
+    KeyBinding::new("<Home>", "Jump to 0"),
+    KeyBinding::new("<End>", "Jump to 255"),
+    KeyBinding::new("<=>", "Set a value").shown_as("Set"),
+    KeyBinding::new("<W>", "Watch for a value"),
+    KeyBinding::new("<</>>", "Halve/double work duration"),
+    KeyBinding::new("<1-4>", "Toggle panels"),
+    KeyBinding::new("<S>", "Toggle sync increments"),
+    KeyBinding::new("<P>", "Pause").shown_as("Pause"),
+    KeyBinding::new("<F5>", "Freeze for a screenshot"),
+    KeyBinding::new("<D>", "Toggle message counts"),
+    KeyBinding::new("<F3>", "Toggle frame timings"),
+    KeyBinding::new("<:>", "Command palette"),
+    KeyBinding::new("<?>", "Help").shown_as("Help"),
+    KeyBinding::new("<Q>", "Quit").shown_as("Quit"),
+    KeyBinding::new("<Esc>", "Close help"),
+];
+
+/// The instructions line for `bindings`: each one with an instruction, as its
+/// name in `strings` followed by its key in `key_color`.
+fn render_instructions(
+    bindings: &[KeyBinding],
+    strings: &Strings,
+    key_color: Color,
+) -> Line<'static> {
+    let mut line = Line::default();
+    for binding in bindings {
+        if let Some(instruction) = binding.instruction {
+            line.push_span(format!(" {} ", strings.instruction(instruction)));
+            line.push_span(binding.key.fg(key_color).bold());
+        }
+    }
+    line.push_span(" ");
+    line
 }
 
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
 pub enum AppMessage {
-    Draw,
     UpdateCount(u8),
+    /// A counter update along with the label and source of the increment
+    /// that caused it, which are recorded in the audit trail.
+    UpdateCountWithMetadata {
+        value: u8,
+        label: String,
+        source: String,
+    },
+    /// Percentage complete of the in-flight increment. At 100 its work is
+    /// done, and an update follows only if that changed the value.
+    UpdateProgress(u8),
+    /// How many messages are waiting for the counter, sent as they're queued
+    /// and as they're taken off the queue.
+    QueueDepth(usize),
     HandleKey(KeyEvent),
+    /// A terminal event; keys go through [`AppMessage::HandleKey`]'s path.
+    HandleEvent(Event),
+    Increment,
+    Decrement,
+    /// Increments by one right away, skipping the counter's work, and replies
+    /// with the new value. An increment already being worked on still lands
+    /// when it finishes.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    IncrementNow(RpcReplyPort<u8>),
+    /// Replies with the changes to the counter after the given sequence
+    /// number, for replaying onto the value as of that change.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_events_since"
+        )
+    )]
+    EventsSince(u64, RpcReplyPort<Vec<ReplayEvent>>),
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    GetCounter(RpcReplyPort<u8>),
+    /// Has the counter take on a value right away.
+    SetValue(u8),
+    SetTitle(String),
+    /// Marks whether the session is being recorded, by whatever's recording
+    /// it, which the title shows until it stops.
+    SetRecording(bool),
+    /// Shows a transient message for the default toast duration.
+    Notify(String, Style),
+    /// Sent periodically by the [`Ticker`](crate::Ticker).
+    Tick,
+    /// Shows another counter under the given name, alongside the main one.
+    /// Registering a name again replaces its counter.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_named_actor"
+        )
+    )]
+    RegisterCounter(String, ActorRef<CounterMessage>),
+    /// Swaps in a different main counter, which is told the app is ready.
+    /// The app otherwise keeps the one it was started with.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_actor"
+        )
+    )]
+    SetCounterRef(ActorRef<CounterMessage>),
+    /// A new value from the registered counter with the given name.
+    UpdateNamedCount(String, u8),
+    /// New values for several registered counters, drawn together. If any
+    /// name isn't registered, none are applied.
+    BatchUpdate(Vec<(String, u8)>),
+    /// Switches increments between going through the counter actor, with
+    /// its simulated work, and applying immediately in the app.
+    ToggleSyncMode,
+    /// Replaces the values the sparkline is drawn from, oldest first.
+    UpdateHistory(VecDeque<u64>),
+    /// The system's memory pressure changed.
+    SetMemoryPressure(MemoryPressureLevel),
+    /// Adds a panel to the layout, unless one with the same ID is present.
+    AddPanel(Panel),
+    /// Removes the panel with the given ID.
+    RemovePanel(String),
+    Resize,
+    Pause(PauseReason),
+    /// Lifts a pause taken for the given reason; a pause for another reason
+    /// stays in effect.
+    Resume(PauseReason),
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
     ShouldExit(RpcReplyPort<bool>),
+    /// Replies with a receiver that turns true once the app is asked to
+    /// exit, for waiting on that without asking again and again.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    WatchExit(RpcReplyPort<watch::Receiver<bool>>),
+    /// Has the app exit at once, without asking about unsaved changes, for
+    /// when there's no one left to ask.
+    Quit,
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    Snapshot(RpcReplyPort<AppSnapshot>),
+    /// Runs a command from a control script.
+    Run(ScriptCommand),
+    /// Opens a dialog over whatever else is showing.
+    ShowModal(Modal),
+    /// Sent by the app to itself when changes held back by the redraw window
+    /// are due to be drawn.
+    Redraw,
+    /// Sent by the app to itself to pass the increments gathered over
+    /// the increment window to the counter.
+    FlushIncrements,
+    /// Replies with how many messages of each variant the app has handled.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    MessageCounts(RpcReplyPort<MessageCounts>),
+}
+
+impl AppMessage {
+    /// The variant's name, for tracing.
+    fn message_type(&self) -> &'static str {
+        match self {
+            AppMessage::UpdateCount(_) => "UpdateCount",
+            AppMessage::UpdateCountWithMetadata { .. } => "UpdateCountWithMetadata",
+            AppMessage::UpdateProgress(_) => "UpdateProgress",
+            AppMessage::QueueDepth(_) => "QueueDepth",
+            AppMessage::HandleKey(_) => "HandleKey",
+            AppMessage::HandleEvent(_) => "HandleEvent",
+            AppMessage::Increment => "Increment",
+            AppMessage::Decrement => "Decrement",
+            AppMessage::IncrementNow(_) => "IncrementNow",
+            AppMessage::EventsSince(..) => "EventsSince",
+            AppMessage::GetCounter(_) => "GetCounter",
+            AppMessage::SetValue(_) => "SetValue",
+            AppMessage::SetTitle(_) => "SetTitle",
+            AppMessage::SetRecording(_) => "SetRecording",
+            AppMessage::Notify(..) => "Notify",
+            AppMessage::Tick => "Tick",
+            AppMessage::RegisterCounter(..) => "RegisterCounter",
+            AppMessage::SetCounterRef(_) => "SetCounterRef",
+            AppMessage::UpdateNamedCount(..) => "UpdateNamedCount",
+            AppMessage::BatchUpdate(_) => "BatchUpdate",
+            AppMessage::ToggleSyncMode => "ToggleSyncMode",
+            AppMessage::UpdateHistory(_) => "UpdateHistory",
+            AppMessage::SetMemoryPressure(_) => "SetMemoryPressure",
+            AppMessage::AddPanel(_) => "AddPanel",
+            AppMessage::RemovePanel(_) => "RemovePanel",
+            AppMessage::Resize => "Resize",
+            AppMessage::Pause(_) => "Pause",
+            AppMessage::Resume(_) => "Resume",
+            AppMessage::ShouldExit(_) => "ShouldExit",
+            AppMessage::WatchExit(_) => "WatchExit",
+            AppMessage::Quit => "Quit",
+            AppMessage::Snapshot(_) => "Snapshot",
+            AppMessage::Run(_) => "Run",
+            AppMessage::ShowModal(_) => "ShowModal",
+            AppMessage::Redraw => "Redraw",
+            AppMessage::FlushIncrements => "FlushIncrements",
+            AppMessage::MessageCounts(_) => "MessageCounts",
+        }
+    }
+}
+
+impl std::fmt::Display for AppMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppMessage::UpdateCount(new) => write!(f, "AppMessage::UpdateCount({})", new),
+            AppMessage::UpdateCountWithMetadata {
+                value,
+                label,
+                source,
+            } => write!(
+                f,
+                "AppMessage::UpdateCountWithMetadata({}, {:?}, {:?})",
+                value, label, source
+            ),
+            AppMessage::UpdateProgress(pct) => write!(f, "AppMessage::UpdateProgress({})", pct),
+            AppMessage::QueueDepth(depth) => write!(f, "AppMessage::QueueDepth({})", depth),
+            AppMessage::HandleKey(evt) => write!(f, "AppMessage::HandleKey({})", evt.code),
+            AppMessage::HandleEvent(event) => write!(f, "AppMessage::HandleEvent({:?})", event),
+            AppMessage::Increment => write!(f, "AppMessage::Increment"),
+            AppMessage::Decrement => write!(f, "AppMessage::Decrement"),
+            AppMessage::IncrementNow(_) => write!(f, "AppMessage::IncrementNow"),
+            AppMessage::EventsSince(seq, _) => write!(f, "AppMessage::EventsSince({})", seq),
+            AppMessage::GetCounter(_) => write!(f, "AppMessage::GetCounter"),
+            AppMessage::SetValue(value) => write!(f, "AppMessage::SetValue({})", value),
+            AppMessage::SetTitle(title) => write!(f, "AppMessage::SetTitle({:?})", title),
+            AppMessage::SetRecording(recording) => {
+                write!(f, "AppMessage::SetRecording({})", recording)
+            }
+            AppMessage::Notify(text, _) => write!(f, "AppMessage::Notify({:?})", text),
+            AppMessage::Tick => write!(f, "AppMessage::Tick"),
+            AppMessage::RegisterCounter(name, _) => {
+                write!(f, "AppMessage::RegisterCounter({:?})", name)
+            }
+            AppMessage::SetCounterRef(actor) => {
+                write!(f, "AppMessage::SetCounterRef({})", actor.get_id())
+            }
+            AppMessage::UpdateNamedCount(name, value) => {
+                write!(f, "AppMessage::UpdateNamedCount({:?}, {})", name, value)
+            }
+            AppMessage::BatchUpdate(updates) => {
+                write!(f, "AppMessage::BatchUpdate({:?})", updates)
+            }
+            AppMessage::ToggleSyncMode => write!(f, "AppMessage::ToggleSyncMode"),
+            AppMessage::UpdateHistory(history) => {
+                write!(f, "AppMessage::UpdateHistory({:?})", history)
+            }
+            AppMessage::SetMemoryPressure(level) => {
+                write!(f, "AppMessage::SetMemoryPressure({})", level)
+            }
+            AppMessage::AddPanel(panel) => write!(f, "AppMessage::AddPanel({:?})", panel),
+            AppMessage::RemovePanel(id) => write!(f, "AppMessage::RemovePanel({:?})", id),
+            AppMessage::Resize => write!(f, "AppMessage::Resize"),
+            AppMessage::Pause(reason) => write!(f, "AppMessage::Pause({:?})", reason),
+            AppMessage::Resume(reason) => write!(f, "AppMessage::Resume({:?})", reason),
+            AppMessage::ShouldExit(_) => write!(f, "AppMessage::ShouldExit"),
+            AppMessage::WatchExit(_) => write!(f, "AppMessage::WatchExit"),
+            AppMessage::Quit => write!(f, "AppMessage::Quit"),
+            AppMessage::Snapshot(_) => write!(f, "AppMessage::Snapshot"),
+            AppMessage::Run(command) => write!(f, "AppMessage::Run({:?})", command),
+            AppMessage::ShowModal(modal) => write!(f, "AppMessage::ShowModal({:?})", modal.title),
+            AppMessage::Redraw => write!(f, "AppMessage::Redraw"),
+            AppMessage::FlushIncrements => write!(f, "AppMessage::FlushIncrements"),
+            AppMessage::MessageCounts(_) => write!(f, "AppMessage::MessageCounts"),
+        }
+    }
+}
+
+/// Typed access to a running [`App`], hiding its message protocol.
+#[derive(Debug, Clone)]
+pub struct AppHandle {
+    actor: ActorRef<AppMessage>,
+}
+
+impl AppHandle {
+    /// What the app registers as.
+    pub const NAME: &str = APP_ACTOR_NAME;
+
+    pub fn new(actor_ref: ActorRef<AppMessage>) -> Self {
+        Self { actor: actor_ref }
+    }
+
+    /// Whether the app has been asked to exit.
+    pub async fn exit(&self) -> Result<bool> {
+        Ok(call_timeout!(self.actor, AppMessage::ShouldExit)?)
+    }
+
+    /// A receiver that turns true once the app has been asked to exit.
+    pub async fn watch_exit(&self) -> Result<watch::Receiver<bool>> {
+        Ok(call_timeout!(self.actor, AppMessage::WatchExit)?)
+    }
+
+    /// Waits until the app asks to exit or `stopped` turns true, which it
+    /// should once the app stops for any reason, including a failed handler,
+    /// so this never waits on a dead actor.
+    pub async fn wait_for_exit(&self, mut stopped: watch::Receiver<bool>) -> Result<()> {
+        let stopping = stopped.wait_for(|stopped| *stopped);
+        match self.watch_exit().await {
+            Ok(mut exiting) => tokio::select! {
+                _ = stopping => {}
+                // An error means the app has gone, which `stopping` covers.
+                _ = exiting.wait_for(|exiting| *exiting) => {}
+            },
+            // Already gone, or going.
+            Err(_) => {
+                stopping.await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn increment(&self) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Increment)?)
+    }
+
+    pub async fn decrement(&self) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Decrement)?)
+    }
+
+    pub async fn increment_now(&self) -> Result<u8> {
+        Ok(call_timeout!(self.actor, AppMessage::IncrementNow)?)
+    }
+
+    pub async fn events_since(&self, seq: u64) -> Result<Vec<ReplayEvent>> {
+        Ok(call_timeout!(self.actor, |reply| AppMessage::EventsSince(
+            seq, reply
+        ))?)
+    }
+
+    pub async fn get_counter(&self) -> Result<u8> {
+        Ok(call_timeout!(self.actor, AppMessage::GetCounter)?)
+    }
+
+    pub async fn snapshot(&self) -> Result<AppSnapshot> {
+        Ok(call_timeout!(self.actor, AppMessage::Snapshot)?)
+    }
+
+    pub async fn message_counts(&self) -> Result<MessageCounts> {
+        Ok(call_timeout!(self.actor, AppMessage::MessageCounts)?)
+    }
+
+    pub async fn show_modal(&self, modal: Modal) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::ShowModal(modal))?)
+    }
+
+    pub async fn run(&self, command: ScriptCommand) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Run(command))?)
+    }
+
+    pub async fn set_value(&self, value: u8) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::SetValue(value))?)
+    }
+
+    pub async fn quit(&self) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Quit)?)
+    }
+
+    pub async fn batch_update(&self, updates: Vec<(String, u8)>) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::BatchUpdate(updates))?)
+    }
+
+    pub async fn set_title(&self, title: String) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::SetTitle(title))?)
+    }
+
+    pub async fn set_recording(&self, recording: bool) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::SetRecording(recording))?)
+    }
+
+    pub async fn notify(&self, text: String, style: Style) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Notify(text, style))?)
+    }
+
+    pub async fn handle_key(&self, evt: KeyEvent) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::HandleKey(evt))?)
+    }
+
+    pub async fn resize(&self) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Resize)?)
+    }
+
+    pub async fn pause(&self, reason: PauseReason) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Pause(reason))?)
+    }
+
+    pub async fn resume(&self, reason: PauseReason) -> Result<()> {
+        Ok(cast!(self.actor, AppMessage::Resume(reason))?)
+    }
+
+    pub fn stop(&self) {
+        self.actor.stop(None);
+    }
 }
 
 impl Actor for App {
@@ -42,11 +737,31 @@ impl Actor for App {
         _myself: ractor::ActorRef<Self::Msg>,
         args: Self::Arguments,
     ) -> Result<Self::State, ractor::ActorProcessingErr> {
-        Ok(AppState {
-            counter: 0,
-            exit: false,
-            tui: Arc::new(Mutex::new(args.tui)),
-        })
+        // The spawn in `main` waits on this, so a terminal that never
+        // answers would hang startup instead of failing it. Asking it blocks,
+        // so that's done off the runtime, leaving the timeout free to fire.
+        let timeout = args.startup_timeout;
+        let setup = tokio::task::spawn_blocking(move || {
+            validate(&args)?;
+            Ok::<_, AppStartupError>(AppState::new(args))
+        });
+        match tokio::time::timeout(timeout, setup).await {
+            Ok(state) => Ok(state??),
+            Err(_) => {
+                tracing::error!("App setup took longer than {:?}", timeout);
+                Err(StartupTimeoutError { after: timeout }.into())
+            }
+        }
+    }
+
+    async fn post_start(
+        &self,
+        myself: ractor::ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        // We're up, so the counter can start sending updates.
+        state.counter_handle.app_ready(myself.clone()).await?;
+        state.draw_if_dirty(&myself).await
     }
 
     async fn handle(
@@ -55,95 +770,3613 @@ impl Actor for App {
         message: Self::Msg,
         state: &mut Self::State,
     ) -> Result<(), ractor::ActorProcessingErr> {
-        match message {
-            AppMessage::Draw => {
-                tracing::info!("Drawing screen");
-                let mut tui = state.tui.lock().await;
-                tui.draw(|frame| state.draw(frame))?;
-                tracing::info!("Drew screen");
-            }
-            AppMessage::UpdateCount(new) => {
-                tracing::info!("Got counter update: {}", new);
-                state.counter = new;
-                tracing::info!("Sending draw request");
-                cast!(myself, AppMessage::Draw)?;
-                tracing::info!("Assigned counter update: {}", new);
-            }
-            AppMessage::ShouldExit(reply) => {
-                tracing::info!("Got exit check");
-                reply.send(state.exit)?;
-                tracing::info!("Replied to exit check");
-            }
-            AppMessage::HandleKey(evt) => {
-                tracing::info!("Got key event {:?}", evt);
-                state.handle_key_event(evt);
-                tracing::info!("Handled key event {:?}", evt);
+        let span = tracing::info_span!(
+            "app.handle",
+            app.counter = state.counter,
+            app.message_type = message.message_type(),
+        );
+        state.messages.record(message.message_type());
+        async {
+            match message {
+                AppMessage::UpdateCount(new) => {
+                    tracing::info!("Got counter update: {}", new);
+                    state.set_counter(ReplayOp::Update(new));
+                    tracing::info!("Assigned counter update: {}", new);
+                }
+                AppMessage::UpdateCountWithMetadata {
+                    value,
+                    label,
+                    source,
+                } => {
+                    tracing::info!("Got counter update: {} from {} ({})", value, source, label);
+                    state.apply_update(value, label, source);
+                }
+                AppMessage::UpdateProgress(pct) => {
+                    tracing::info!("Got progress update: {}%", pct);
+                    state.progress = (pct < 100).then_some(pct);
+                    if state.frozen_at.is_none() {
+                        state.spinner_frame = state.spinner_frame.wrapping_add(1);
+                    }
+                    state.dirty = true;
+                }
+                AppMessage::QueueDepth(depth) => {
+                    if depth != state.queue_depth {
+                        state.queue_depth = depth;
+                        state.dirty = true;
+                    }
+                }
+                AppMessage::ShouldExit(reply) => {
+                    tracing::info!("Got exit check");
+                    reply.send(state.exit)?;
+                    tracing::info!("Replied to exit check");
+                }
+                AppMessage::WatchExit(reply) => reply.send(state.exit_signal.subscribe())?,
+                AppMessage::HandleKey(evt) => {
+                    tracing::info!("Got key event {:?}", evt);
+                    state.handle_key_event(evt).await?;
+                    tracing::info!("Handled key event {:?}", evt);
+                }
+                AppMessage::HandleEvent(event) => state.handle_event(event).await?,
+                AppMessage::Increment => state.increment_counter().await?,
+                AppMessage::Decrement => state.decrement_counter().await?,
+                AppMessage::EventsSince(seq, reply) => {
+                    reply.send(state.replay.since(seq))?;
+                }
+                AppMessage::IncrementNow(reply) => {
+                    let value = state.counter.saturating_add(1);
+                    state.apply_update(value, "increment now".to_string(), "rpc".to_string());
+                    reply.send(value)?;
+                }
+                AppMessage::SetValue(value) => state.counter_handle.set_value(value).await?,
+                AppMessage::Quit => state.exit(),
+                AppMessage::Run(command) => state.run_script(command).await?,
+                AppMessage::ShowModal(modal) => state.set_mode(DisplayMode::Modal(modal)),
+                // Drawn below, now the window has passed.
+                AppMessage::Redraw => state.redraw_scheduled = false,
+                AppMessage::FlushIncrements => {
+                    state.increments_scheduled = false;
+                    state.flush_increments().await?;
+                }
+                AppMessage::MessageCounts(reply) => {
+                    reply.send(state.messages.clone())?;
+                }
+                AppMessage::Snapshot(reply) => {
+                    reply.send(state.snapshot())?;
+                }
+                AppMessage::GetCounter(reply) => {
+                    reply.send(state.counter)?;
+                }
+                AppMessage::SetTitle(title) => {
+                    tracing::info!("Setting title to {:?}", title);
+                    state.title = title;
+                    state.dirty = true;
+                }
+                AppMessage::SetRecording(recording) => {
+                    tracing::info!("Recording: {}", recording);
+                    state.recording = recording;
+                    state.dirty = true;
+                }
+                AppMessage::Notify(text, style) => state.notify(text, style),
+                AppMessage::Tick => state.tick().await?,
+                AppMessage::RegisterCounter(name, actor) => {
+                    state
+                        .register_counter(name, CounterHandle::new(actor), myself.clone())
+                        .await?
+                }
+                AppMessage::SetCounterRef(actor) => {
+                    tracing::info!("Switching to counter {}", actor.get_id());
+                    state.counter_handle = CounterHandle::new(actor);
+                    state.counter_handle.app_ready(myself.clone()).await?;
+                }
+                AppMessage::UpdateNamedCount(name, value) => state.set_named_counter(&name, value),
+                AppMessage::BatchUpdate(updates) => state.batch_update(updates),
+                AppMessage::ToggleSyncMode => state.toggle_sync_mode(),
+                AppMessage::UpdateHistory(mut history) => {
+                    history.drain(..history.len().saturating_sub(HISTORY_CAPACITY));
+                    state.history = history;
+                    state.dirty = true;
+                }
+                AppMessage::SetMemoryPressure(level) => {
+                    state.memory_pressure = level;
+                    state.dirty = true;
+                }
+                AppMessage::AddPanel(panel) => state.add_panel(panel),
+                AppMessage::RemovePanel(id) => state.remove_panel(&PanelId(id)),
+                AppMessage::Resize => {
+                    tracing::info!("Got resize");
+                    state.dirty = true;
+                }
+                AppMessage::Pause(reason) => {
+                    tracing::info!("Pausing rendering: {:?}", reason);
+                    state.pause(reason).await?;
+                }
+                AppMessage::Resume(reason) => {
+                    tracing::info!("Resuming rendering: {:?}", reason);
+                    state.resume(reason).await?;
+                }
             }
+            state.schedule_increments(&myself);
+            // Every handler that changes what's on screen marks the state dirty;
+            // drawing happens here so no handler has to remember to request it.
+            state.draw_if_dirty(&myself).await
         }
-        Ok(())
+        .instrument(span)
+        .await
+    }
+}
+
+/// Checks the app's arguments before anything is built from them, catching
+/// what would otherwise only fail on the first draw, or later.
+fn validate(args: &AppArgs) -> Result<(), AppStartupError> {
+    match args.tui.size() {
+        Ok(size) if size.width > 0 && size.height > 0 => {}
+        _ => return Err(AppStartupError::InvalidTerminal),
+    }
+    if !(MIN_WORK_DURATION..=MAX_WORK_DURATION).contains(&args.work_duration) {
+        return Err(AppStartupError::InvalidConfig(format!(
+            "work duration {:?} isn't between {:?} and {:?}",
+            args.work_duration, MIN_WORK_DURATION, MAX_WORK_DURATION
+        )));
+    }
+    if !args.counter.is_running() {
+        return Err(AppStartupError::MissingCounterRef);
+    }
+    Ok(())
+}
+
+/// The ASCII stand-in for a box-drawing glyph from any of ratatui's border
+/// sets.
+fn ascii_border(symbol: &str) -> Option<&'static str> {
+    match symbol {
+        "┌" | "┐" | "└" | "┘" | "╭" | "╮" | "╰" | "╯" | "┏" | "┓" | "┗" | "┛" | "╔" | "╗" | "╚"
+        | "╝" => Some("+"),
+        "─" | "━" | "═" => Some("-"),
+        "│" | "┃" | "║" => Some("|"),
+        _ => None,
     }
 }
 
+/// Forwards an entry to the audit trail in `namespace`, if one is running.
+fn record_audit(namespace: Option<&str>, entry: AuditEntry) {
+    let Some(audit) = ractor::registry::where_is(namespaced(namespace, AUDIT_ACTOR_NAME)) else {
+        tracing::warn!("No audit trail; dropping entry {:?}", entry);
+        return;
+    };
+    let audit: ActorRef<AuditMessage> = audit.into();
+    if let Err(e) = cast!(audit, AuditMessage::Record(entry)) {
+        tracing::warn!("Failed to record audit entry: {}", e);
+    }
+}
+
+/// The parts of [`AppState`] worth comparing or saving, detached from the
+/// terminal and the actors it talks to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppSnapshot {
+    pub counter: u8,
+    pub exit: bool,
+    pub title: String,
+    /// The latest values, oldest first.
+    pub history: Vec<u8>,
+    pub display_mode: DisplayMode,
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
 pub struct AppState {
     counter: u8,
+    // Announced whenever the counter arrives at it.
+    watch_target: Option<u8>,
+    // What the counter panel shows, stepping toward `animating_to` on each
+    // tick after a jump.
+    displayed: u8,
+    animating_to: Option<u8>,
+    animation_step: u8,
+    title: String,
     exit: bool,
-    tui: Arc<Mutex<Terminal<CrosstermBackend<Stdout>>>>,
+    // Changes to the counter since the last export, worth confirming before
+    // quitting over.
+    unsaved_changes: bool,
+    // Follows `exit`, for those waiting on it.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    exit_signal: watch::Sender<bool>,
+    dirty: bool,
+    paused: Option<PauseReason>,
+    // Set while something outside the app records the session.
+    recording: bool,
+    // Since when ticks and the spinner have been held; set with F5.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    frozen_at: Option<Instant>,
+    // Whether the terminal has focus, as last reported by it.
+    focused: bool,
+    progress: Option<u8>,
+    spinner_frame: usize,
+    reduce_motion: bool,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    counter_handle: CounterHandle,
+    // Registered alongside the main counter, in registration order.
+    counters: Vec<NamedCounter>,
+    // Which of `counters` the arrow keys act on; `None` for the main one.
+    focus: Option<usize>,
+    work_duration: Duration,
+    // Increments skip the counter actor and apply immediately.
+    sync_mode: bool,
+    // Messages waiting for the counter, as last reported by it.
+    queue_depth: usize,
+    mode: DisplayMode,
+    // When the current run of Right presses began, and its latest press.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    hold_started: Option<Instant>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    last_press: Option<Instant>,
+    active_panels: Vec<Panel>,
+    // What the up and down keys scroll; may name a panel no longer active.
+    focused_panel: PanelId,
+    // How many of the newest values or log entries are scrolled past.
+    history_scroll: usize,
+    log_scroll: usize,
+    memory_pressure: MemoryPressureLevel,
+    log: VecDeque<String>,
+    // The latest values, oldest first, widened for `Sparkline`.
+    history: VecDeque<u64>,
+    value_stats: ValueStats,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    replay: ReplayLog,
+    show_sparkline: bool,
+    align: VerticalAlign,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    toasts: ToastQueue,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    frame_cache: Option<FrameCache>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    demo: Option<Demo>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    updates: VecDeque<Instant>,
+    // Handled by this actor, counted for the debug overlay.
+    messages: MessageCounts,
+    debug_overlay: bool,
+    // The counter's own counts, as of its latest reply.
+    counter_messages: MessageCounts,
+    frame_timings: FrameTimings,
+    profiler_overlay: bool,
+    redraw_window: Duration,
+    ascii_borders: bool,
+    theme: Theme,
+    strings: Strings,
+    capabilities: Capabilities,
+    reset_confirm_above: u8,
+    cancel_confirm_above: Option<u8>,
+    namespace: Option<String>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    last_draw: Option<Instant>,
+    // A `Redraw` is on its way, so changes in the window needn't send another.
+    redraw_scheduled: bool,
+    // Steps waiting to go to the counter together, and whether a
+    // `FlushIncrements` is on its way to send them.
+    pending_increments: u8,
+    increments_scheduled: bool,
+    // Owned outright rather than shared: only this actor draws, and its
+    // handlers never run concurrently, so there's nothing to lock against.
+    // Taken out for the length of each draw so the drawing closure can
+    // borrow the rest of the state; `None` only then.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    tui: Option<Box<dyn DrawTarget>>,
 }
 
 impl AppState {
-    fn draw(&self, frame: &mut Frame) {
-        frame.render_widget(self, frame.area());
+    fn new(args: AppArgs) -> Self {
+        AppState {
+            counter: 0,
+            watch_target: None,
+            displayed: 0,
+            animating_to: None,
+            animation_step: 0,
+            // Padded as the border's other titles are.
+            title: format!(" {} ", args.strings.title),
+            exit: false,
+            unsaved_changes: false,
+            exit_signal: watch::Sender::new(false),
+            dirty: true,
+            paused: None,
+            recording: false,
+            frozen_at: None,
+            focused: true,
+            progress: None,
+            spinner_frame: 0,
+            reduce_motion: args.reduce_motion,
+            counter_handle: args.counter,
+            counters: Vec::new(),
+            focus: None,
+            work_duration: args.work_duration,
+            sync_mode: false,
+            queue_depth: 0,
+            mode: DisplayMode::Normal,
+            hold_started: None,
+            last_press: None,
+            active_panels: vec![Panel::CounterPanel],
+            focused_panel: Panel::CounterPanel.id(),
+            history_scroll: 0,
+            log_scroll: 0,
+            memory_pressure: MemoryPressureLevel::Low,
+            log: VecDeque::new(),
+            history: VecDeque::new(),
+            value_stats: ValueStats::default(),
+            replay: ReplayLog::default(),
+            show_sparkline: args.show_sparkline,
+            align: args.align,
+            toasts: ToastQueue::default(),
+            frame_cache: None,
+            demo: args.demo.then(|| Demo::start(Instant::now())),
+            updates: VecDeque::new(),
+            messages: MessageCounts::default(),
+            debug_overlay: false,
+            counter_messages: MessageCounts::default(),
+            frame_timings: FrameTimings::default(),
+            profiler_overlay: false,
+            redraw_window: args.redraw_window,
+            ascii_borders: args.ascii_borders,
+            theme: if args.capabilities.truecolor {
+                args.theme
+            } else {
+                args.theme.without_truecolor()
+            },
+            strings: args.strings,
+            capabilities: args.capabilities,
+            reset_confirm_above: args.reset_confirm_above,
+            cancel_confirm_above: args.cancel_confirm_above,
+            namespace: args.namespace,
+            last_draw: None,
+            redraw_scheduled: false,
+            pending_increments: 0,
+            increments_scheduled: false,
+            tui: Some(args.tui),
+        }
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Left => self.decrement_counter(),
-            KeyCode::Right => {
-                let ctr: ActorRef<CounterMessage> =
-                    ractor::registry::where_is("counter".to_string())
-                        .expect("Counter???")
-                        .into();
-                cast!(ctr, CounterMessage::IncrementCounter(self.counter)).unwrap();
+    async fn draw_if_dirty(
+        &mut self,
+        myself: &ActorRef<AppMessage>,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        // While paused the state keeps changing but nothing is drawn; `dirty`
+        // stays set so the latest state shows up on resume.
+        if !self.dirty || self.paused.is_some() {
+            return Ok(());
+        }
+        // Too soon after the last draw: leave `dirty` set and have a `Redraw`
+        // pick up everything that changes in the meantime. Once exiting there
+        // may be no later message to draw on, so the last frame goes at once.
+        if let Some(last_draw) = self.last_draw
+            && !self.exit
+        {
+            let due = last_draw + self.redraw_window;
+            let now = Instant::now();
+            if now < due {
+                if !self.redraw_scheduled {
+                    self.redraw_scheduled = true;
+                    myself.send_after(due - now, || AppMessage::Redraw);
+                }
+                return Ok(());
             }
-            _ => {}
         }
+        self.draw_now().await
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    async fn draw_now(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Drawing screen");
+        self.expire_updates(Instant::now());
+        let mut tui = self
+            .tui
+            .take()
+            .expect("the terminal is only out during a draw");
+        let started = Instant::now();
+        self.last_draw = Some(started);
+        let mut drawn = tui.draw(&mut |frame| self.draw(frame));
+        if let Err(e) = &drawn {
+            // Often a one-off, such as a write cut short. Clearing starts the
+            // next draw afresh rather than diffing against a screen that may
+            // not be what ratatui thinks it is.
+            tracing::warn!("Draw failed, clearing and retrying once: {}", e);
+            drawn = tui
+                .clear()
+                .and_then(|()| tui.draw(&mut |frame| self.draw(frame)));
+        }
+        self.tui = Some(tui);
+        let area = drawn?;
+        let elapsed = started.elapsed();
+        // Shown from the next draw on; redrawing to show it would never stop.
+        self.frame_timings.record(elapsed);
+        if elapsed > FRAME_BUDGET {
+            tracing::warn!(
+                "Frame took {:?}, over the {:?} budget ({}x{})",
+                elapsed,
+                FRAME_BUDGET,
+                area.width,
+                area.height
+            );
+        }
+        self.dirty = false;
+        tracing::info!("Drew screen");
+        Ok(())
     }
 
-    fn decrement_counter(&mut self) {
-        self.counter -= 1;
+    async fn pause(&mut self, reason: PauseReason) -> Result<(), ractor::ActorProcessingErr> {
+        // A user pause outranks a focus pause: losing focus while paused by
+        // the user must not hide the overlay on return.
+        if self.paused == Some(PauseReason::UserRequested) {
+            return Ok(());
+        }
+        self.paused = Some(reason);
+        if reason == PauseReason::UserRequested {
+            // Draw the overlay once; further draws are held until resume.
+            self.draw_now().await?;
+        }
+        Ok(())
     }
-}
 
-impl Widget for &AppState {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let title = Line::from(" Counter App Tutorial ".bold());
-        let instructions = Line::from(vec![
-            " Decrement ".into(),
-            "<Left>".blue().bold(),
-            " Increment ".into(),
-            "<Right>".blue().bold(),
-            " Quit ".into(),
-            "<Q> ".blue().bold(),
-        ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+    async fn resume(&mut self, reason: PauseReason) -> Result<(), ractor::ActorProcessingErr> {
+        if self.paused != Some(reason) {
+            return Ok(());
+        }
+        self.paused = None;
+        if reason == PauseReason::FocusLost {
+            // The terminal may have been resized while we weren't drawing.
+            if let Some(tui) = &mut self.tui {
+                tui.autoresize()?;
+            }
+        }
+        self.dirty = true;
+        Ok(())
+    }
 
-        let counter_text = Text::from(vec![Line::from(vec![
-            "Value: ".into(),
-            self.counter.to_string().yellow(),
-        ])]);
+    fn add_panel(&mut self, panel: Panel) {
+        if self.active_panels.iter().all(|p| p.id() != panel.id()) {
+            tracing::info!("Adding panel {:?}", panel);
+            self.active_panels.push(panel);
+            self.dirty = true;
+        }
+    }
 
-        Paragraph::new(counter_text)
-            .centered()
-            .block(block)
-            .render(area, buf);
+    fn remove_panel(&mut self, id: &PanelId) {
+        tracing::info!("Removing panel {:?}", id);
+        self.active_panels.retain(|p| p.id() != *id);
+        self.dirty = true;
+    }
+
+    fn toggle_panel(&mut self, panel: Panel) {
+        let id = panel.id();
+        if self.active_panels.iter().any(|p| p.id() == id) {
+            self.remove_panel(&id);
+        } else {
+            self.add_panel(panel);
+        }
+    }
+
+    fn notify(&mut self, text: String, style: Style) {
+        tracing::info!("Notifying {:?}", text);
+        self.toasts.push(text, style, DEFAULT_TOAST_DURATION);
+        self.dirty = true;
+    }
+
+    /// Like [`AppState::notify`], but gone again before long.
+    fn flash(&mut self, text: &str) {
+        tracing::info!("Flashing {:?}", text);
+        self.toasts
+            .push(text.to_string(), Style::new().yellow(), FLASH_DURATION);
+        self.dirty = true;
+    }
+
+    /// Sets the focused counter outright. Setting a counter cancels its
+    /// running task, so nothing in flight lands on top of the jump.
+    async fn jump_to(&mut self, value: u8) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Jumping to {}", value);
+        match self.focus {
+            Some(i) => self.counters[i].handle.set_value(value).await?,
+            None => {
+                self.counter_handle.set_value(value).await?;
+                self.progress = None;
+                self.dirty = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// The value the arrow keys act on.
+    fn focused_value(&self) -> u8 {
+        match self.focus {
+            Some(i) => self.counters[i].value,
+            None => self.counter,
+        }
+    }
+
+    fn push_log(&mut self, entry: String) {
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry);
+    }
+
+    async fn register_counter(
+        &mut self,
+        name: String,
+        handle: CounterHandle,
+        app: ActorRef<AppMessage>,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Registering counter {:?}", name);
+        handle.app_ready(app).await?;
+        match self
+            .counters
+            .iter_mut()
+            .find(|counter| counter.name == name)
+        {
+            Some(counter) => counter.handle = handle,
+            None => self.counters.push(NamedCounter {
+                name,
+                value: 0,
+                handle,
+            }),
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn set_named_counter(&mut self, name: &str, value: u8) {
+        match self
+            .counters
+            .iter_mut()
+            .find(|counter| counter.name == name)
+        {
+            Some(counter) => {
+                counter.value = value;
+                self.dirty = true;
+            }
+            None => tracing::warn!("Update for unregistered counter {:?}", name),
+        }
+    }
+
+    /// Checks every name before applying any value, so a bad batch leaves
+    /// the counters as they were. The draw at the end of the message covers
+    /// the whole batch.
+    fn batch_update(&mut self, updates: Vec<(String, u8)>) {
+        let unknown = updates
+            .iter()
+            .filter(|(name, _)| !self.counters.iter().any(|counter| &counter.name == name))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            let text = format!("Batch not applied; unknown: {}", unknown.join(", "));
+            tracing::warn!("{}", text);
+            self.notify(text, Style::new().red());
+            return;
+        }
+        tracing::info!("Applying a batch of {} updates", updates.len());
+        for (name, value) in updates {
+            self.set_named_counter(&name, value);
+        }
+    }
+
+    /// Holds still everything that changes without a key press, for taking a
+    /// clean screenshot: ticks, and with them toasts expiring and the demo,
+    /// and the spinner. Keys still work and show.
+    fn toggle_freeze(&mut self) {
+        match self.frozen_at.take() {
+            Some(frozen_at) => {
+                tracing::info!("Unfreezing");
+                // Pick the demo up where it was, not with every step at once.
+                if let Some(demo) = &mut self.demo {
+                    demo.delay(frozen_at.elapsed());
+                }
+            }
+            None => {
+                tracing::info!("Freezing");
+                self.frozen_at = Some(Instant::now());
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Moves panel focus, which decides what the up and down keys scroll, to
+    /// the next active panel, wrapping around.
+    fn focus_next_panel(&mut self) {
+        let next = self
+            .active_panels
+            .iter()
+            .position(|panel| panel.id() == self.focused_panel)
+            .map_or(0, |i| (i + 1) % self.active_panels.len());
+        if let Some(panel) = self.active_panels.get(next) {
+            self.focused_panel = panel.id();
+            self.dirty = true;
+        }
+    }
+
+    /// Scrolls the focused panel, if it's one that scrolls: the counter
+    /// panel's sparkline or the log.
+    fn scroll(&mut self, direction: Scroll) {
+        let (offset, len, min_shown) = if self.focused_panel == Panel::CounterPanel.id() {
+            // The sparkline needs two values to graph.
+            (&mut self.history_scroll, self.history.len(), 2)
+        } else if self.focused_panel == Panel::LogPanel.id() {
+            (&mut self.log_scroll, self.log.len(), 1)
+        } else {
+            return;
+        };
+        let max = len.saturating_sub(min_shown);
+        let scrolled = match direction {
+            Scroll::Older => (*offset + 1).min(max),
+            Scroll::Newer => offset.saturating_sub(1),
+        };
+        if scrolled != *offset {
+            *offset = scrolled;
+            self.dirty = true;
+        }
+    }
+
+    /// Moves focus to the next registered counter, wrapping back around to
+    /// the main one.
+    fn focus_next(&mut self) {
+        self.focus = match self.focus {
+            None if !self.counters.is_empty() => Some(0),
+            Some(i) if i + 1 < self.counters.len() => Some(i + 1),
+            _ => None,
+        };
+        self.dirty = true;
+    }
+
+    /// Takes on a new value, logging and auditing where it came from.
+    fn apply_update(&mut self, value: u8, label: String, source: String) {
+        self.set_counter(if label == "reset" {
+            ReplayOp::Reset
+        } else {
+            ReplayOp::Update(value)
+        });
+        self.push_log(format!("{} from {} ({})", value, source, label));
+        record_audit(
+            self.namespace.as_deref(),
+            AuditEntry {
+                value,
+                label,
+                source,
+            },
+        );
+    }
+
+    fn toggle_profiler_overlay(&mut self) {
+        self.profiler_overlay = !self.profiler_overlay;
+        self.dirty = true;
+    }
+
+    async fn toggle_debug_overlay(&mut self) {
+        self.debug_overlay = !self.debug_overlay;
+        if self.debug_overlay {
+            self.refresh_counter_messages().await;
+        }
+        self.dirty = true;
+    }
+
+    /// Keeps the last counts on failure; a stale overlay beats a failed
+    /// handler over diagnostics.
+    async fn refresh_counter_messages(&mut self) {
+        match self.counter_handle.get_stats().await {
+            Ok(stats) => {
+                if stats.messages != self.counter_messages {
+                    self.counter_messages = stats.messages;
+                    self.dirty = true;
+                }
+            }
+            Err(e) => tracing::warn!("Couldn't fetch the counter's message counts: {}", e),
+        }
+    }
+
+    fn toggle_sync_mode(&mut self) {
+        self.sync_mode = !self.sync_mode;
+        let text = if self.sync_mode {
+            "Increments apply immediately"
+        } else {
+            "Increments go through the counter"
+        };
+        self.notify(text.to_string(), Style::new().cyan());
+    }
+
+    fn set_counter(&mut self, op: ReplayOp) {
+        let value = op.value();
+        if self.watch_target == Some(value) && self.counter != value {
+            let text = format!("Reached {}", value);
+            self.push_log(text.clone());
+            self.notify(text, Style::new().green());
+        }
+        self.replay.push(op);
+        self.counter = value;
+        self.unsaved_changes = true;
+        self.animate_to(value);
+        broadcast_count(self.namespace.as_deref(), value);
+        if self.history.len() == HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(value.into());
+        self.value_stats.record(value);
+        self.progress = None;
+        self.record_update(Instant::now());
+        self.dirty = true;
+    }
+
+    /// Shows `value`, counting to it over a few ticks if it's more than one
+    /// away and motion is allowed.
+    fn animate_to(&mut self, value: u8) {
+        let distance = self.displayed.abs_diff(value);
+        if distance > 1 && !self.reduce_motion {
+            self.animating_to = Some(value);
+            self.animation_step = distance.div_ceil(ANIMATION_FRAMES);
+        } else {
+            self.displayed = value;
+            self.animating_to = None;
+        }
+    }
+
+    /// Advances everything the ticker drives, which holds still while
+    /// frozen.
+    async fn tick(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        if self.frozen_at.is_some() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        self.step_animation();
+        if self.toasts.expire(now) {
+            self.dirty = true;
+        }
+        // Updates age out of the rate with no new ones to trigger a draw.
+        if self.expire_updates(now) {
+            self.dirty = true;
+        }
+        while let Some(command) = self.demo.as_mut().and_then(|d| d.next_due(now)) {
+            self.run_command(command).await?;
+        }
+        if self.debug_overlay {
+            self.refresh_counter_messages().await;
+        }
+        Ok(())
+    }
+
+    /// Moves the shown value one step closer to where it's animating to.
+    fn step_animation(&mut self) {
+        let Some(target) = self.animating_to else {
+            return;
+        };
+        self.displayed = if self.displayed < target {
+            self.displayed
+                .saturating_add(self.animation_step)
+                .min(target)
+        } else {
+            self.displayed
+                .saturating_sub(self.animation_step)
+                .max(target)
+        };
+        if self.displayed == target {
+            self.animating_to = None;
+        }
+        self.dirty = true;
+    }
+
+    fn record_update(&mut self, at: Instant) {
+        self.updates.push_back(at);
+        self.expire_updates(at);
+    }
+
+    /// Drops updates older than [`RATE_WINDOW`], returning whether any were
+    /// dropped.
+    fn expire_updates(&mut self, now: Instant) -> bool {
+        let before = self.updates.len();
+        while let Some(&oldest) = self.updates.front() {
+            if now.duration_since(oldest) <= RATE_WINDOW {
+                break;
+            }
+            self.updates.pop_front();
+        }
+        self.updates.len() != before
+    }
+
+    /// Counter updates per second over the last [`RATE_WINDOW`].
+    fn rate(&self) -> f64 {
+        self.updates.len() as f64 / RATE_WINDOW.as_secs_f64()
+    }
+
+    fn progress_line(&self, pct: u8, theme: &Theme) -> Line<'static> {
+        let pct = format!("{}%", pct);
+        if self.reduce_motion {
+            return Line::from(vec!["Working: ".into(), pct.into()]);
+        }
+        let spinner = SPINNER[self.spinner_frame % SPINNER.len()].fg(theme.progress);
+        Line::from(vec![
+            "Working ".into(),
+            if self.frozen_at.is_some() {
+                spinner
+            } else {
+                spinner.slow_blink()
+            },
+            " ".into(),
+            pct.into(),
+        ])
+    }
+
+    /// Draws the body over the cached frame, rendering the frame again only
+    /// when the terminal size or something shown on it has changed.
+    fn draw(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+        let title = self.build_title();
+        let stale = self.frame_cache.as_ref().is_none_or(|cache| {
+            cache.area != area || cache.title != title || cache.mode != self.mode
+        });
+        if stale {
+            tracing::info!("Rendering frame");
+            let mut buffer = Buffer::empty(area);
+            self.render_frame(area, &self.theme, &mut buffer);
+            self.frame_cache = Some(FrameCache {
+                area,
+                title,
+                mode: self.mode.clone(),
+                buffer,
+            });
+        }
+        if let Some(cache) = &self.frame_cache {
+            frame.buffer_mut().merge(&cache.buffer);
+        }
+        self.render_body(area, &self.theme, frame.buffer_mut());
+        self.asciify_borders(area, frame.buffer_mut());
+        self.dim_unfocused(area, frame.buffer_mut());
+    }
+
+    /// Swaps box-drawing glyphs for ASCII once everything is rendered, which
+    /// catches every widget's borders without each one knowing. Each glyph
+    /// is one cell wide, as its replacement is, so titles fit as before.
+    fn asciify_borders(&self, area: Rect, buf: &mut Buffer) {
+        if !self.ascii_borders {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = &mut buf[(x, y)];
+                if let Some(ascii) = ascii_border(cell.symbol()) {
+                    cell.set_symbol(ascii);
+                }
+            }
+        }
+    }
+
+    /// Dims everything while the terminal doesn't have focus.
+    fn dim_unfocused(&self, area: Rect, buf: &mut Buffer) {
+        if !self.focused {
+            buf.set_style(area, Style::new().dim());
+        }
+    }
+
+    async fn handle_event(&mut self, event: Event) -> Result<(), ractor::ActorProcessingErr> {
+        match event {
+            // Skip repeats, which crossterm emits on Windows; presses drive
+            // the app and releases end a held Right.
+            Event::Key(key_event)
+                if matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Release) =>
+            {
+                tracing::info!("Got key event {:?}", key_event);
+                self.handle_key_event(key_event).await?;
+            }
+            Event::Resize(_, _) => {
+                tracing::info!("Got resize");
+                self.dirty = true;
+            }
+            // Never asked for without support, so a stray one is noise that
+            // would otherwise pause the app with no way to unpause it.
+            Event::FocusLost if self.capabilities.focus_reporting => {
+                self.set_focused(false).await?;
+                self.pause(PauseReason::FocusLost).await?;
+            }
+            Event::FocusGained if self.capabilities.focus_reporting => {
+                self.set_focused(true).await?;
+                self.resume(PauseReason::FocusLost).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Draws right away, even while paused, so the dimming or its end shows
+    /// before a focus pause holds further drawing.
+    async fn set_focused(&mut self, focused: bool) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Terminal focused: {}", focused);
+        self.focused = focused;
+        self.draw_now().await
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        // Only reported by terminals with keyboard enhancements; elsewhere a
+        // hold ends when the presses stop.
+        if key_event.kind == KeyEventKind::Release {
+            if key_event.code == KeyCode::Right {
+                self.release_hold();
+            }
+            return Ok(());
+        }
+        match self.mode {
+            DisplayMode::Normal => {}
+            DisplayMode::Input(_) | DisplayMode::WatchInput(_) => {
+                return self.handle_input_key(key_event).await;
+            }
+            DisplayMode::Help => {
+                if let KeyCode::Esc | KeyCode::Char('?') = key_event.code {
+                    self.set_mode(DisplayMode::Normal);
+                }
+                return Ok(());
+            }
+            DisplayMode::Modal(_) => return self.handle_modal_key(key_event).await,
+            DisplayMode::Palette(_) => return self.handle_palette_key(key_event).await,
+        }
+        self.handle_normal_key(key_event.code).await
+    }
+
+    /// What a key press does in normal mode.
+    async fn handle_normal_key(&mut self, code: KeyCode) -> Result<(), ractor::ActorProcessingErr> {
+        match code {
+            KeyCode::Char('q') => self.request_quit(),
+            KeyCode::Char('?') => self.set_mode(DisplayMode::Help),
+            KeyCode::Char('s') => self.toggle_sync_mode(),
+            KeyCode::Char('d') => self.toggle_debug_overlay().await,
+            KeyCode::F(3) => self.toggle_profiler_overlay(),
+            KeyCode::Char('=') => self.set_mode(DisplayMode::Input(String::new())),
+            KeyCode::Char('w') => self.set_mode(DisplayMode::WatchInput(String::new())),
+            KeyCode::Char('p') => self.toggle_pause().await?,
+            KeyCode::Char(':') => self.set_mode(DisplayMode::Palette(Palette::default())),
+            KeyCode::F(5) => self.toggle_freeze(),
+            KeyCode::Tab => self.focus_next(),
+            KeyCode::BackTab => self.focus_next_panel(),
+            KeyCode::Up => self.scroll(Scroll::Older),
+            KeyCode::Down => self.scroll(Scroll::Newer),
+            // Past either end, the work would leave the value as it is.
+            KeyCode::Left if self.focused_value() == u8::MIN => {
+                self.flash(&self.strings.at_min.clone())
+            }
+            KeyCode::Right if self.focused_value() == u8::MAX => {
+                self.flash(&self.strings.at_max.clone())
+            }
+            KeyCode::Left => match self.focus {
+                Some(i) => {
+                    let counter = &self.counters[i];
+                    counter.handle.decrement(counter.value).await?
+                }
+                None => {
+                    if !self.confirm_cancel(ModalAction::Decrement) {
+                        self.decrement_counter().await?
+                    }
+                }
+            },
+            KeyCode::Right => match self.focus {
+                Some(i) => {
+                    let counter = &self.counters[i];
+                    counter
+                        .handle
+                        .increment_with_metadata(
+                            counter.value,
+                            "increment".to_string(),
+                            APP_ACTOR_NAME.to_string(),
+                        )
+                        .await?
+                }
+                None => {
+                    if !self.confirm_cancel(ModalAction::Increment) {
+                        self.increment_counter().await?
+                    }
+                }
+            },
+            KeyCode::Home => self.jump_to(u8::MIN).await?,
+            KeyCode::End => self.jump_to(u8::MAX).await?,
+            KeyCode::Char(c @ '1'..='9') => {
+                if let Some(panel) = c.to_digit(10).and_then(Panel::for_key) {
+                    self.toggle_panel(panel);
+                }
+            }
+            KeyCode::Char('<') => {
+                self.set_work_duration((self.work_duration / 2).max(MIN_WORK_DURATION))
+                    .await?
+            }
+            KeyCode::Char('>') => {
+                self.set_work_duration(self.work_duration.saturating_mul(2).min(MAX_WORK_DURATION))
+                    .await?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Keys while a dialog is open: the arrows pick a button, Enter accepts
+    /// it and Esc dismisses the dialog. Anything else is ignored.
+    async fn handle_modal_key(
+        &mut self,
+        key_event: KeyEvent,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        let DisplayMode::Modal(modal) = &mut self.mode else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Left => modal.select_previous(),
+            KeyCode::Right => modal.select_next(),
+            KeyCode::Char('y') if modal.is_confirm() => {
+                modal.select_confirm();
+                return self.accept_modal().await;
+            }
+            KeyCode::Enter => return self.accept_modal().await,
+            KeyCode::Char('n') if !modal.is_confirm() => return Ok(()),
+            KeyCode::Esc | KeyCode::Char('n') => {
+                tracing::info!("Dismissed modal {:?}", modal.title);
+                self.set_mode(DisplayMode::Normal);
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Closes the modal with its selected button, running the action it
+    /// confirms, if any.
+    async fn accept_modal(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        let DisplayMode::Modal(modal) = &self.mode else {
+            return Ok(());
+        };
+        let entry = format!("{}: {}", modal.title, modal.selected());
+        let action = modal.accepted_action();
+        tracing::info!("Accepted modal {:?}", entry);
+        self.push_log(entry);
+        self.set_mode(DisplayMode::Normal);
+        match action {
+            Some(ModalAction::Reset) => self.run_command(ScriptCommand::Reset).await?,
+            Some(ModalAction::Increment) => self.increment_counter().await?,
+            Some(ModalAction::Decrement) => self.decrement_counter().await?,
+            Some(ModalAction::Quit) => self.exit(),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Asks whether to go ahead with `action` if it would cancel a task
+    /// further along than `cancel_confirm_above`, returning whether it
+    /// asked. Accepting runs the action.
+    fn confirm_cancel(&mut self, action: ModalAction) -> bool {
+        let (Some(pct), Some(threshold)) = (self.progress, self.cancel_confirm_above) else {
+            return false;
+        };
+        if pct < threshold {
+            return false;
+        }
+        self.confirm(
+            self.strings.cancel_task.clone(),
+            self.strings
+                .cancel_prompt
+                .replace("{pct}", &pct.to_string()),
+            self.strings.cancel_confirm.clone(),
+            action,
+        );
+        true
+    }
+
+    /// Resets at once unless that would throw away more than
+    /// `reset_confirm_above`, in which case it asks first. Only for resets
+    /// someone is there to confirm; scripts reset outright.
+    async fn request_reset(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        if self.counter <= self.reset_confirm_above {
+            return self.run_command(ScriptCommand::Reset).await;
+        }
+        self.confirm(
+            self.strings.reset.clone(),
+            self.strings
+                .reset_prompt
+                .replace("{value}", &self.counter.to_string()),
+            self.strings.reset.clone(),
+            ModalAction::Reset,
+        );
+        Ok(())
+    }
+
+    /// Quits at once unless there are changes that haven't been exported,
+    /// in which case it asks first.
+    fn request_quit(&mut self) {
+        if !self.unsaved_changes {
+            return self.exit();
+        }
+        self.confirm(
+            self.strings.quit.clone(),
+            self.strings.unsaved_prompt.clone(),
+            self.strings.quit.clone(),
+            ModalAction::Quit,
+        );
+    }
+
+    /// Opens a [`Modal::confirm`] dialog with the string set's Cancel.
+    fn confirm(&mut self, title: String, text: String, confirm: String, action: ModalAction) {
+        let modal =
+            Modal::confirm(title, text, confirm, action).with_cancel(self.strings.cancel.clone());
+        self.set_mode(DisplayMode::Modal(modal));
+    }
+
+    /// Keys while typing a value: digits edit it, Enter sets the counter and
+    /// Esc gives up.
+    async fn handle_input_key(
+        &mut self,
+        key_event: KeyEvent,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        let (DisplayMode::Input(input) | DisplayMode::WatchInput(input)) = &mut self.mode else {
+            return Ok(());
+        };
+        match key_event.code {
+            // Three digits covers every u8.
+            KeyCode::Char(c @ '0'..='9') if input.len() < 3 => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let input = std::mem::take(input);
+                let watching = matches!(self.mode, DisplayMode::WatchInput(_));
+                self.mode = DisplayMode::Normal;
+                match input.parse::<u8>() {
+                    Ok(value) if watching => self.watch_for(Some(value)),
+                    Ok(value) => self.counter_handle.set_value(value).await?,
+                    Err(_) if watching && input.is_empty() => self.watch_for(None),
+                    Err(_) => self.notify(
+                        format!("{:?} isn't a value from 0 to 255", input),
+                        Style::new().red(),
+                    ),
+                }
+            }
+            KeyCode::Esc => self.mode = DisplayMode::Normal,
+            _ => return Ok(()),
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn watch_for(&mut self, target: Option<u8>) {
+        tracing::info!("Watching for {:?}", target);
+        self.watch_target = target;
+        let text = match target {
+            Some(target) => format!("Watching for {}", target),
+            None => "Stopped watching".to_string(),
+        };
+        self.notify(text, Style::new().cyan());
+    }
+
+    async fn toggle_pause(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        match self.paused {
+            Some(PauseReason::UserRequested) => self.resume(PauseReason::UserRequested).await,
+            _ => self.pause(PauseReason::UserRequested).await,
+        }
+    }
+
+    async fn handle_palette_key(
+        &mut self,
+        key_event: KeyEvent,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        let DisplayMode::Palette(palette) = &mut self.mode else {
+            return Ok(());
+        };
+        match key_event.code {
+            // Control chords aren't typing.
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                palette.push(c)
+            }
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Up => palette.select_previous(),
+            KeyCode::Down => palette.select_next(),
+            KeyCode::Enter => {
+                let action = palette.selected();
+                self.set_mode(DisplayMode::Normal);
+                if let Some(action) = action {
+                    self.run_palette_action(action).await?;
+                }
+            }
+            KeyCode::Esc => self.set_mode(DisplayMode::Normal),
+            _ => return Ok(()),
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Does what the action's key would, from normal mode, by pressing it:
+    /// whatever the key checks or asks first, the action does too.
+    async fn run_palette_action(
+        &mut self,
+        action: PaletteAction,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Running palette action {:?}", action);
+        match action.key() {
+            Some(code) => self.handle_normal_key(code).await,
+            // Resetting has no key of its own.
+            None => self.request_reset().await,
+        }
+    }
+
+    fn set_mode(&mut self, mode: DisplayMode) {
+        tracing::info!("Entering {:?} mode", mode);
+        self.mode = mode;
+        self.dirty = true;
+    }
+
+    /// The bottom line of the frame, listing what keys do in this mode.
+    fn instructions(&self, theme: &Theme) -> Line<'_> {
+        match &self.mode {
+            DisplayMode::Normal => render_instructions(&KEY_BINDINGS, &self.strings, theme.key),
+            DisplayMode::Input(input) => Line::from(vec![
+                format!(" {}: ", self.strings.value).into(),
+                format!("{}_", input).yellow(),
+                format!(" {} ", self.strings.commit).into(),
+                "<Enter>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.cancel).into(),
+                "<Esc> ".fg(theme.key).bold(),
+            ]),
+            DisplayMode::WatchInput(input) => Line::from(vec![
+                format!(" {}: ", self.strings.watch_for).into(),
+                format!("{}_", input).yellow(),
+                format!(" {} ", self.strings.watch).into(),
+                "<Enter>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.cancel).into(),
+                "<Esc> ".fg(theme.key).bold(),
+            ]),
+            DisplayMode::Help => Line::from(vec![
+                format!(" {} ", self.strings.close).into(),
+                "<Esc> ".fg(theme.key).bold(),
+            ]),
+            DisplayMode::Modal(_) => Line::from(vec![
+                format!(" {} ", self.strings.choose).into(),
+                "<Left/Right>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.accept).into(),
+                "<Enter>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.dismiss).into(),
+                "<Esc> ".fg(theme.key).bold(),
+            ]),
+            DisplayMode::Palette(_) => Line::from(vec![
+                format!(" {} ", self.strings.filter).into(),
+                "<type>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.choose).into(),
+                "<Up/Down>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.run).into(),
+                "<Enter>".fg(theme.key).bold(),
+                format!(" {} ", self.strings.close).into(),
+                "<Esc> ".fg(theme.key).bold(),
+            ]),
+        }
+    }
+
+    fn render_help(&self, area: Rect, theme: &Theme, buf: &mut Buffer) {
+        let lines = KEY_BINDINGS
+            .iter()
+            .map(|binding| {
+                Line::from(vec![
+                    format!("{:>7} ", binding.key).fg(theme.key).bold(),
+                    self.strings
+                        .description(binding.key, binding.description)
+                        .to_string()
+                        .into(),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let [overlay] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [overlay] = Layout::horizontal([Constraint::Length(40)])
+            .flex(Flex::Center)
+            .areas(overlay);
+        Clear.render(overlay, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(format!(" {} ", self.strings.keys)))
+            .render(overlay, buf);
+    }
+
+    /// Per-variant message counts for the app and its counter, busiest
+    /// first, in the bottom-left corner.
+    fn render_debug_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines = Vec::new();
+        for (name, counts) in [("App", &self.messages), ("Counter", &self.counter_messages)] {
+            lines.push(Line::from(format!("{} ({})", name, counts.total()).bold()));
+            lines.extend(
+                counts
+                    .busiest()
+                    .into_iter()
+                    .map(|(kind, count)| Line::from(format!("  {:>6} {}", count, kind))),
+            );
+        }
+        let [overlay] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::End)
+            .areas(area);
+        let [overlay] = Layout::horizontal([Constraint::Length(34)])
+            .flex(Flex::Start)
+            .areas(overlay);
+        Clear.render(overlay, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(" Messages "))
+            .render(overlay, buf);
+    }
+
+    /// How long the latest draws took, in the top-right corner. Each draw
+    /// shows the timings up to the one before it.
+    fn render_profiler_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let timing = |elapsed: Option<Duration>| match elapsed {
+            Some(elapsed) => format!("{:.1?}", elapsed),
+            None => "-".to_string(),
+        };
+        let lines = vec![
+            Line::from(format!("Last  {}", timing(self.frame_timings.last()))),
+            Line::from(format!("Avg   {}", timing(self.frame_timings.average()))),
+            Line::from(format!("Draws {}", self.frame_timings.draws())),
+        ];
+        let [overlay] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Start)
+            .areas(area);
+        let [overlay] = Layout::horizontal([Constraint::Length(22)])
+            .flex(Flex::End)
+            .areas(overlay);
+        Clear.render(overlay, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(" Frames "))
+            .render(overlay, buf);
+    }
+
+    pub fn snapshot(&self) -> AppSnapshot {
+        AppSnapshot {
+            counter: self.counter,
+            exit: self.exit,
+            title: self.title.clone(),
+            history: self
+                .history
+                .iter()
+                .map(|&value| u8::try_from(value).unwrap_or(u8::MAX))
+                .collect(),
+            display_mode: self.mode.clone(),
+        }
+    }
+
+    /// Puts back what [`AppState::snapshot`] took. Only the app's copy of the
+    /// counter changes; the counter actor picks it up with the next change,
+    /// which always starts from the app's value.
+    pub fn restore(&mut self, snap: AppSnapshot) {
+        self.counter = snap.counter;
+        self.displayed = snap.counter;
+        self.animating_to = None;
+        self.exit = snap.exit;
+        self.exit_signal.send_replace(snap.exit);
+        self.title = snap.title;
+        self.history = snap.history.into_iter().map(u64::from).collect();
+        self.history
+            .drain(..self.history.len().saturating_sub(HISTORY_CAPACITY));
+        self.mode = snap.display_mode;
+        self.dirty = true;
+    }
+
+    async fn run_command(
+        &mut self,
+        command: ScriptCommand,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        tracing::info!("Running script command {:?}", command);
+        match command {
+            ScriptCommand::Increment => self.increment_counter().await?,
+            ScriptCommand::Decrement => self.decrement_counter().await?,
+            ScriptCommand::Reset => self.counter_handle.reset().await?,
+            ScriptCommand::Set(value) => self.counter_handle.set_value(value).await?,
+            ScriptCommand::Title(title) => {
+                self.title = title;
+                self.dirty = true;
+            }
+            ScriptCommand::Pause => self.pause(PauseReason::UserRequested).await?,
+            ScriptCommand::Resume => self.resume(PauseReason::UserRequested).await?,
+            ScriptCommand::Export(path) => self.export(&path),
+            ScriptCommand::Quit => self.exit(),
+        }
+        Ok(())
+    }
+
+    /// Like [`AppState::run_command`], but quitting asks first as Q does:
+    /// unlike the demo, a script may have someone at the terminal to ask.
+    async fn run_script(
+        &mut self,
+        command: ScriptCommand,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match command {
+            ScriptCommand::Quit => {
+                self.request_quit();
+                Ok(())
+            }
+            command => self.run_command(command).await,
+        }
+    }
+
+    /// Failing to write is shown rather than failing the handler, and leaves
+    /// the changes unsaved.
+    fn export(&mut self, path: &std::path::Path) {
+        let text = self
+            .replay
+            .since(0)
+            .iter()
+            .map(|event| format!("{}\n", event.op.value()))
+            .collect::<String>();
+        match std::fs::write(path, text) {
+            Ok(()) => {
+                tracing::info!("Exported the history to {}", path.display());
+                self.unsaved_changes = false;
+                self.notify(
+                    format!("Exported to {}", path.display()),
+                    Style::new().green(),
+                );
+            }
+            Err(e) => {
+                tracing::warn!("Couldn't export to {}: {}", path.display(), e);
+                self.notify(
+                    format!("Couldn't export to {}: {}", path.display(), e),
+                    Style::new().red(),
+                );
+            }
+        }
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+        self.exit_signal.send_replace(true);
+    }
+
+    async fn increment_counter(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        let step = self.increment_step(Instant::now());
+        if self.sync_mode {
+            let value = self.counter.saturating_add(step);
+            self.apply_update(
+                value,
+                "sync increment".to_string(),
+                APP_ACTOR_NAME.to_string(),
+            );
+            return Ok(());
+        }
+        self.pending_increments = self.pending_increments.saturating_add(step);
+        Ok(())
+    }
+
+    /// Has the gathered increments sent once the window is up, unless
+    /// that's already arranged.
+    fn schedule_increments(&mut self, myself: &ActorRef<AppMessage>) {
+        if self.pending_increments > 0 && !self.increments_scheduled {
+            self.increments_scheduled = true;
+            myself.send_after(INCREMENT_WINDOW, || AppMessage::FlushIncrements);
+        }
+    }
+
+    /// Sends every increment gathered so far to the counter as one task.
+    async fn flush_increments(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        let times = std::mem::take(&mut self.pending_increments);
+        if times == 0 {
+            return Ok(());
+        }
+        tracing::info!("Sending {} increments from {}", times, self.counter);
+        self.counter_handle
+            .increment_by(
+                self.counter,
+                times,
+                "increment".to_string(),
+                APP_ACTOR_NAME.to_string(),
+            )
+            .await?;
+        self.progress = Some(0);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// The step for a Right press at `now`, growing the longer the key has
+    /// been held. Terminals differ in how they report a held key, so a hold
+    /// is inferred from presses arriving within [`HOLD_TIMEOUT`] of each
+    /// other.
+    fn increment_step(&mut self, now: Instant) -> u8 {
+        let held = match (self.hold_started, self.last_press) {
+            (Some(started), Some(last)) if now - last <= HOLD_TIMEOUT => now - started,
+            _ => {
+                self.hold_started = Some(now);
+                Duration::ZERO
+            }
+        };
+        self.last_press = Some(now);
+        ACCELERATION
+            .iter()
+            .rev()
+            .find(|(after, _)| held >= *after)
+            .map_or(1, |(_, step)| *step)
+    }
+
+    fn release_hold(&mut self) {
+        self.hold_started = None;
+        self.last_press = None;
+    }
+
+    async fn set_work_duration(
+        &mut self,
+        duration: Duration,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        self.counter_handle.set_work_duration(duration).await?;
+        self.work_duration = duration;
+        self.notify(
+            format!("Work duration {:.1}s", duration.as_secs_f64()),
+            Style::new().cyan(),
+        );
+        Ok(())
+    }
+
+    /// Like [`AppState::increment_counter`], the new value arrives from the
+    /// counter once its work is done, unless in sync mode.
+    async fn decrement_counter(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        if self.sync_mode {
+            let value = self.counter.saturating_sub(1);
+            self.apply_update(
+                value,
+                "sync decrement".to_string(),
+                APP_ACTOR_NAME.to_string(),
+            );
+            return Ok(());
+        }
+        self.counter_handle.decrement(self.counter).await?;
+        self.progress = Some(0);
+        self.dirty = true;
+        Ok(())
+    }
+}
+
+/// Renders the counter into `area` of a buffer the caller owns, for embedding
+/// in a larger layout instead of taking over the whole frame.
+pub fn render_into(state: &AppState, area: Rect, buf: &mut Buffer) {
+    AppWidget::new(state).render(area, buf);
+}
+
+/// The part of a one-row `area` that centers `line`, measured in terminal
+/// columns so wide glyphs don't push it off center.
+fn centered_line_area(line: &Line, area: Rect) -> Rect {
+    let width = line
+        .spans
+        .iter()
+        .map(|span| span.content.width())
+        .sum::<usize>()
+        .min(area.width as usize) as u16;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        width,
+        ..area
+    }
+}
+
+impl AppState {
+    fn render_counter_panel(&self, area: Rect, theme: &Theme, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(vec![
+                format!("{}: ", self.strings.rate).into(),
+                format!("{:.2}/s", self.rate()).dim(),
+            ]),
+            Line::from(vec![
+                format!("{}: ", self.strings.work).into(),
+                format!("{:.1}s", self.work_duration.as_secs_f64()).dim(),
+            ]),
+        ];
+        if let Some(pct) = self.progress {
+            lines.push(self.progress_line(pct, theme));
+        }
+        let text_height = 1 + self.counters.len() as u16 + lines.len() as u16;
+        let [above, text_area, below] = Layout::vertical(match self.align {
+            VerticalAlign::Top => [
+                Constraint::Length(0),
+                Constraint::Length(text_height),
+                Constraint::Min(0),
+            ],
+            VerticalAlign::Center => [
+                Constraint::Fill(1),
+                Constraint::Length(text_height),
+                Constraint::Fill(1),
+            ],
+            VerticalAlign::Bottom => [
+                Constraint::Min(0),
+                Constraint::Length(text_height),
+                Constraint::Length(0),
+            ],
+        })
+        .areas(area);
+        let [value_area, counters_area, details_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(self.counters.len() as u16),
+            Constraint::Min(0),
+        ])
+        .areas(text_area);
+        let mut value = Line::from(vec![
+            format!("{}: ", self.strings.value).into(),
+            self.displayed.to_string().fg(theme.value),
+        ]);
+        // Only worth marking the focus when there's a choice.
+        if self.focus.is_none() && !self.counters.is_empty() {
+            value = value.reversed();
+        }
+        let value_area = centered_line_area(&value, value_area);
+        value.render(value_area, buf);
+
+        let rows = Layout::vertical(self.counters.iter().map(|_| Constraint::Length(1)))
+            .split(counters_area);
+        for (i, (counter, &row)) in self.counters.iter().zip(rows.iter()).enumerate() {
+            let mut line = Line::from(vec![
+                format!("{}: ", counter.name).into(),
+                counter.value.to_string().fg(theme.value),
+            ]);
+            if self.focus == Some(i) {
+                line = line.reversed();
+            }
+            let row = centered_line_area(&line, row);
+            line.render(row, buf);
+        }
+
+        Paragraph::new(Text::from(lines))
+            .centered()
+            .render(details_area, buf);
+        if self.show_sparkline {
+            // In whichever space the text left, favoring below it.
+            let sparkline_area = if above.height > below.height {
+                above
+            } else {
+                below
+            };
+            self.render_sparkline(sparkline_area, theme, buf);
+        }
+    }
+
+    /// Graphs the newest values that fit, scaled to the tallest of them.
+    fn render_sparkline(&self, area: Rect, theme: &Theme, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        if self.history.len() < 2 {
+            // Not enough to graph; a flat line keeps the layout steady.
+            let bottom = Rect {
+                y: area.bottom() - 1,
+                height: 1,
+                ..area
+            };
+            Line::from("▁".repeat(area.width as usize).dim()).render(bottom, buf);
+            return;
+        }
+        Sparkline::default()
+            .data(self.sparkline_data(area.width as usize))
+            .fg(theme.sparkline)
+            .render(area, buf);
+    }
+
+    /// The values a sparkline `width` columns wide graphs, oldest first.
+    /// Scrolling pages back through older values.
+    fn sparkline_data(&self, width: usize) -> Vec<u64> {
+        let end = self.history.len().saturating_sub(self.history_scroll);
+        let shown = end.min(width);
+        self.history.range(end - shown..end).copied().collect()
+    }
+
+    fn render_log_panel(&self, area: Rect, buf: &mut Buffer) {
+        let title = match self.log_scroll {
+            0 => format!(" {} ", self.strings.log),
+            n => format!(" {} (-{}) ", self.strings.log, n),
+        };
+        let block = Block::bordered().title(title);
+        // Show the newest entries that fit, less any scrolled past.
+        let rows = block.inner(area).height as usize;
+        let end = self.log.len().saturating_sub(self.log_scroll);
+        let entries = self.log.range(end.saturating_sub(rows)..end);
+        List::new(entries.map(String::as_str))
+            .block(block)
+            .render(area, buf);
+    }
+
+    fn render_stats_panel(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(format!("{}: {}", self.strings.value, self.counter)),
+            Line::from(format!("{}: {:.2}/s", self.strings.rate, self.rate())),
+            Line::from(format!(
+                "{}: {:.1}s",
+                self.strings.work,
+                self.work_duration.as_secs_f64()
+            )),
+        ];
+        if let Some(mean) = self.value_stats.mean() {
+            lines.push(Line::from(format!(
+                "{}: {}/{}",
+                self.strings.min_max, self.value_stats.min, self.value_stats.max
+            )));
+            lines.push(Line::from(format!("{}: {:.1}", self.strings.avg, mean)));
+        }
+        Paragraph::new(lines)
+            .block(Block::bordered().title(format!(" {} ", self.strings.stats)))
+            .render(area, buf);
+    }
+
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let label = if self.active_panels.len() == 1 {
+            "panel"
+        } else {
+            "panels"
+        };
+        let mut status = Line::from(format!(" {} {}: ", self.active_panels.len(), label).dim());
+        // The focused panel stands out once there's more than one.
+        for (i, panel) in self.active_panels.iter().enumerate() {
+            if i > 0 {
+                status.push_span(", ".dim());
+            }
+            let id = panel.id();
+            if self.active_panels.len() > 1 && id == self.focused_panel {
+                status.push_span(id.0.bold());
+            } else {
+                status.push_span(id.0.dim());
+            }
+        }
+        if self.sync_mode {
+            status.push_span(" | sync".cyan());
+        }
+        if self.queue_depth > 0 {
+            status.push_span(format!(" | queue: {}", self.queue_depth).yellow());
+        }
+        if self.memory_pressure != MemoryPressureLevel::Low {
+            let indicator = format!(" | memory pressure: {}", self.memory_pressure);
+            status.push_span(match self.memory_pressure {
+                MemoryPressureLevel::High => indicator.red(),
+                _ => indicator.yellow(),
+            });
+        }
+        status.render(area, buf);
+    }
+}
+
+fn render_actor_list_panel(area: Rect, buf: &mut Buffer) {
+    let mut names = ractor::registry::registered();
+    names.sort();
+    List::new(names)
+        .block(Block::bordered().title(" Actors "))
+        .render(area, buf);
+}
+
+/// The border, title, and instructions as last rendered, along with what they
+/// were rendered from.
+#[derive(Debug)]
+struct FrameCache {
+    area: Rect,
+    title: Line<'static>,
+    mode: DisplayMode,
+    buffer: Buffer,
+}
+
+impl AppState {
+    /// The title followed by a tag for each mode worth knowing about at a
+    /// glance. Tags carry their own trailing space, as the title does.
+    pub fn build_title(&self) -> Line<'static> {
+        let mut title = Line::from(self.title.clone().bold());
+        if self.paused.is_some() {
+            title.push_span("[paused] ".yellow());
+        }
+        if self.recording {
+            title.push_span("[rec] ".red());
+        }
+        if self.demo.as_ref().is_some_and(|demo| !demo.is_finished()) {
+            title.push_span("[auto] ".green());
+        }
+        if let Some(target) = self.watch_target {
+            title.push_span(format!("[watch {}] ", target).cyan());
+        }
+        title
+    }
+
+    fn frame_block(&self, theme: &Theme) -> Block<'_> {
+        Block::bordered()
+            .title(self.build_title().centered())
+            .title_bottom(self.instructions(theme).centered())
+            .border_set(border::THICK)
+    }
+
+    /// The border with the title and instructions on it, which change far
+    /// less often than what's inside.
+    fn render_frame(&self, area: Rect, theme: &Theme, buf: &mut Buffer) {
+        self.frame_block(theme).render(area, buf);
+    }
+
+    /// Everything inside the frame, including overlays.
+    fn render_body(&self, area: Rect, theme: &Theme, buf: &mut Buffer) {
+        let inner = self.frame_block(theme).inner(area);
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(inner);
+
+        let n = self.active_panels.len() as u32;
+        let areas = Layout::horizontal(self.active_panels.iter().map(|_| Constraint::Ratio(1, n)))
+            .split(main_area);
+        for (panel, &panel_area) in self.active_panels.iter().zip(areas.iter()) {
+            match panel {
+                Panel::CounterPanel => self.render_counter_panel(panel_area, theme, buf),
+                Panel::LogPanel => self.render_log_panel(panel_area, buf),
+                Panel::StatsPanel => self.render_stats_panel(panel_area, buf),
+                Panel::ActorListPanel => render_actor_list_panel(panel_area, buf),
+                Panel::Custom(PanelId(id)) => Block::bordered()
+                    .title(format!(" {} ", id))
+                    .render(panel_area, buf),
+            }
+        }
+
+        self.render_status_bar(status_area, buf);
+
+        if self.debug_overlay {
+            self.render_debug_overlay(main_area, buf);
+        }
+
+        if self.profiler_overlay {
+            self.render_profiler_overlay(main_area, buf);
+        }
+
+        if !self.toasts.is_empty() {
+            self.toasts.render(main_area, buf);
+        }
+
+        match &self.mode {
+            DisplayMode::Help => self.render_help(main_area, theme, buf),
+            DisplayMode::Modal(modal) => modal.render(main_area, buf),
+            DisplayMode::Palette(palette) => palette.render(main_area, buf),
+            DisplayMode::Normal | DisplayMode::Input(_) | DisplayMode::WatchInput(_) => {}
+        }
+
+        if self.paused == Some(PauseReason::UserRequested) {
+            let [overlay] = Layout::vertical([Constraint::Length(3)])
+                .flex(Flex::Center)
+                .areas(area);
+            let [overlay] = Layout::horizontal([Constraint::Length(12)])
+                .flex(Flex::Center)
+                .areas(overlay);
+            Clear.render(overlay, buf);
+            Paragraph::new(self.strings.paused.as_str().bold())
+                .centered()
+                .block(Block::bordered())
+                .render(overlay, buf);
+        }
+    }
+}
+
+/// Draws an [`AppState`] in full, in the app's own theme unless given
+/// another, such as for a screenshot.
+pub struct AppWidget<'a> {
+    state: &'a AppState,
+    theme: &'a Theme,
+}
+
+impl<'a> AppWidget<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self {
+            state,
+            theme: &state.theme,
+        }
+    }
+
+    pub fn theme(mut self, theme: &'a Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+}
+
+impl Widget for AppWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.state.render_frame(area, self.theme, buf);
+        self.state.render_body(area, self.theme, buf);
+        self.state.asciify_borders(area, buf);
+        self.state.dim_unfocused(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use ratatui::{Terminal, backend::TestBackend, buffer::Cell, layout::Size, style::Modifier};
+
+    use super::*;
+    use crate::{snapshot::diff_buffers, testing::Logs};
+
+    /// Stands in for the counter, noting each message it's sent.
+    struct MockCounter;
+
+    impl Actor for MockCounter {
+        type Msg = CounterMessage;
+        type State = Arc<Mutex<Vec<String>>>;
+        type Arguments = Arc<Mutex<Vec<String>>>;
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            received: Self::Arguments,
+        ) -> Result<Self::State, ractor::ActorProcessingErr> {
+            Ok(received)
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            message: Self::Msg,
+            received: &mut Self::State,
+        ) -> Result<(), ractor::ActorProcessingErr> {
+            received.lock().unwrap().push(message.to_string());
+            Ok(())
+        }
+    }
+
+    struct Harness {
+        state: AppState,
+        counter: ActorRef<CounterMessage>,
+        received: Arc<Mutex<Vec<String>>>,
+    }
+
+    /// A terminal that's only ever drawn in memory.
+    fn test_terminal(width: u16, height: u16) -> Box<dyn DrawTarget> {
+        Box::new(Terminal::new(TestBackend::new(width, height)).unwrap())
+    }
+
+    /// What the harness starts an app with, talking to `counter`.
+    fn test_args(counter: &ActorRef<CounterMessage>) -> AppArgs {
+        AppArgs {
+            tui: test_terminal(80, 20),
+            reduce_motion: true,
+            counter: CounterHandle::new(counter.clone()),
+            work_duration: MIN_WORK_DURATION,
+            show_sparkline: true,
+            demo: false,
+            align: VerticalAlign::default(),
+            redraw_window: DEFAULT_REDRAW_WINDOW,
+            startup_timeout: DEFAULT_STARTUP_TIMEOUT,
+            ascii_borders: false,
+            theme: Theme::default(),
+            strings: Strings::default(),
+            capabilities: Capabilities::default(),
+            reset_confirm_above: DEFAULT_RESET_CONFIRM_ABOVE,
+            cancel_confirm_above: None,
+            namespace: None,
+        }
+    }
+
+    impl Harness {
+        async fn new() -> Self {
+            Self::with_args(|_| {}).await
+        }
+
+        /// Starts from [`test_args`] as changed by `configure`.
+        async fn with_args(configure: impl FnOnce(&mut AppArgs)) -> Self {
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let (counter, _) = Actor::spawn(None, MockCounter, received.clone())
+                .await
+                .unwrap();
+            let mut args = test_args(&counter);
+            configure(&mut args);
+            let state = AppState::new(args);
+            Self {
+                state,
+                counter,
+                received,
+            }
+        }
+
+        async fn press(&mut self, code: KeyCode) {
+            self.state
+                .handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+                .await
+                .unwrap();
+        }
+
+        async fn run_palette(&mut self, filter: &str) {
+            self.press(KeyCode::Char(':')).await;
+            for c in filter.chars() {
+                self.press(KeyCode::Char(c)).await;
+            }
+            self.press(KeyCode::Enter).await;
+        }
+
+        /// What an 80x20 terminal would hold, styles and all.
+        fn buffer(&self) -> Buffer {
+            let area = Rect::new(0, 0, 80, 20);
+            let mut buf = Buffer::empty(area);
+            render_into(&self.state, area, &mut buf);
+            buf
+        }
+
+        /// What an 80x20 terminal would show, a line per row.
+        fn screen(&self) -> String {
+            let buf = self.buffer();
+            let area = buf.area;
+            (0..area.height)
+                .map(|y| {
+                    (0..area.width)
+                        .map(|x| buf[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+
+        /// Everything the counter was sent, once it's all been handled.
+        async fn received(self) -> Vec<String> {
+            self.counter
+                .drain_and_wait(Some(Duration::from_secs(1)))
+                .await
+                .unwrap();
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn a_terminal_with_no_room_is_invalid() {
+        let app = Harness::new().await;
+        let mut args = test_args(&app.counter);
+        assert_eq!(validate(&args), Ok(()));
+        args.tui = test_terminal(0, 0);
+        assert_eq!(validate(&args), Err(AppStartupError::InvalidTerminal));
+    }
+
+    /// A terminal that can't say how big it is, as one that isn't a TTY
+    /// can't.
+    #[derive(Debug)]
+    struct SizelessTerminal;
+
+    impl DrawTarget for SizelessTerminal {
+        fn draw(&mut self, _render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+            Err(io::Error::other("not a terminal"))
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            Err(io::Error::other("not a terminal"))
+        }
+
+        fn autoresize(&mut self) -> io::Result<()> {
+            Err(io::Error::other("not a terminal"))
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            Err(io::Error::other("not a terminal"))
+        }
+    }
+
+    #[tokio::test]
+    async fn a_terminal_without_a_size_is_invalid() {
+        let app = Harness::new().await;
+        let mut args = test_args(&app.counter);
+        args.tui = Box::new(SizelessTerminal);
+        assert_eq!(validate(&args), Err(AppStartupError::InvalidTerminal));
+    }
+
+    #[tokio::test]
+    async fn a_work_duration_out_of_range_is_invalid() {
+        let app = Harness::new().await;
+        let mut args = test_args(&app.counter);
+        args.work_duration = MAX_WORK_DURATION + Duration::from_secs(1);
+        let Err(AppStartupError::InvalidConfig(reason)) = validate(&args) else {
+            panic!("expected a bad config");
+        };
+        assert!(reason.starts_with("work duration"), "{}", reason);
+    }
+
+    #[tokio::test]
+    async fn a_stopped_counter_is_missing() {
+        let app = Harness::new().await;
+        let args = test_args(&app.counter);
+        app.counter.stop(None);
+        while app.counter.get_status() != ractor::ActorStatus::Stopped {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(validate(&args), Err(AppStartupError::MissingCounterRef));
+    }
+
+    #[tokio::test]
+    async fn spawning_onto_a_terminal_with_no_room_fails() {
+        let app = Harness::new().await;
+        let mut args = test_args(&app.counter);
+        args.tui = test_terminal(0, 0);
+        let err = match Actor::spawn(None, App, args).await {
+            Err(ractor::SpawnErr::StartupFailed(err)) => err,
+            Err(err) => panic!("expected a failed startup, got {}", err),
+            Ok(_) => panic!("the app started"),
+        };
+        assert_eq!(
+            err.downcast_ref::<AppStartupError>(),
+            Some(&AppStartupError::InvalidTerminal)
+        );
+    }
+
+    /// A terminal that takes a second to say how big it is, as a hung one
+    /// would take forever.
+    #[derive(Debug)]
+    struct UnresponsiveTerminal;
+
+    impl DrawTarget for UnresponsiveTerminal {
+        fn draw(&mut self, _render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+            Ok(Rect::default())
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn autoresize(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(Size::new(80, 20))
+        }
+    }
+
+    /// A test terminal whose next `failures` draws fail, counting the
+    /// clears in between.
+    #[derive(Debug)]
+    struct FlakyTerminal {
+        inner: Box<dyn DrawTarget>,
+        failures: Arc<Mutex<usize>>,
+        clears: Arc<Mutex<usize>>,
+    }
+
+    impl DrawTarget for FlakyTerminal {
+        fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(io::Error::other("write cut short"));
+            }
+            self.inner.draw(render)
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            *self.clears.lock().unwrap() += 1;
+            self.inner.clear()
+        }
+
+        fn autoresize(&mut self) -> io::Result<()> {
+            self.inner.autoresize()
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            self.inner.size()
+        }
+    }
+
+    /// A test terminal counting its draws.
+    #[derive(Debug)]
+    struct CountingTerminal {
+        inner: Box<dyn DrawTarget>,
+        draws: Arc<Mutex<usize>>,
+    }
+
+    impl DrawTarget for CountingTerminal {
+        fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+            *self.draws.lock().unwrap() += 1;
+            self.inner.draw(render)
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            self.inner.clear()
+        }
+
+        fn autoresize(&mut self) -> io::Result<()> {
+            self.inner.autoresize()
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            self.inner.size()
+        }
+    }
+
+    /// How many draws four title changes in quick succession take with a
+    /// redraw window of `window`, straight after a change that was drawn at
+    /// once.
+    async fn draws_for_a_burst(window: Duration) -> usize {
+        let harness = Harness::new().await;
+        let draws = Arc::new(Mutex::new(0));
+        let mut args = test_args(&harness.counter);
+        args.redraw_window = window;
+        args.tui = Box::new(CountingTerminal {
+            inner: test_terminal(80, 20),
+            draws: draws.clone(),
+        });
+        let (actor, handle) = Actor::spawn(None, App, args).await.unwrap();
+        let app = AppHandle::new(actor.clone());
+        // Past the startup draw's window and any redraw it led to, so this
+        // change is drawn as soon as it's handled.
+        tokio::time::sleep(window * 2 + Duration::from_millis(50)).await;
+        app.set_title("first".to_string()).await.unwrap();
+        app.get_counter().await.unwrap();
+        let before = *draws.lock().unwrap();
+
+        for i in 0..4 {
+            app.set_title(format!("title {}", i)).await.unwrap();
+        }
+        tokio::time::sleep(window + Duration::from_millis(100)).await;
+        // Handled after everything before it, so the burst is done with.
+        app.get_counter().await.unwrap();
+        let burst = *draws.lock().unwrap() - before;
+        actor.stop(None);
+        handle.await.unwrap();
+        burst
+    }
+
+    #[tokio::test]
+    async fn a_message_that_changes_nothing_draws_nothing() {
+        let harness = Harness::new().await;
+        let draws = Arc::new(Mutex::new(0));
+        let mut args = test_args(&harness.counter);
+        args.tui = Box::new(CountingTerminal {
+            inner: test_terminal(80, 20),
+            draws: draws.clone(),
+        });
+        let (actor, handle) = Actor::spawn(None, App, args).await.unwrap();
+        let app = AppHandle::new(actor.clone());
+        // Past the startup draw and any redraw it scheduled.
+        tokio::time::sleep(DEFAULT_REDRAW_WINDOW * 2 + Duration::from_millis(50)).await;
+        let before = *draws.lock().unwrap();
+
+        // None of these leave `dirty` set.
+        app.get_counter().await.unwrap();
+        app.snapshot().await.unwrap();
+        app.message_counts().await.unwrap();
+        assert_eq!(*draws.lock().unwrap(), before);
+
+        app.set_title("changed".to_string()).await.unwrap();
+        app.get_counter().await.unwrap();
+        assert_eq!(*draws.lock().unwrap(), before + 1);
+        actor.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn losing_focus_holds_drawing_until_it_comes_back() {
+        let harness = Harness::new().await;
+        let draws = Arc::new(Mutex::new(0));
+        let mut args = test_args(&harness.counter);
+        args.tui = Box::new(CountingTerminal {
+            inner: test_terminal(80, 20),
+            draws: draws.clone(),
+        });
+        let (actor, handle) = Actor::spawn(None, App, args).await.unwrap();
+        let app = AppHandle::new(actor.clone());
+        let settle = || tokio::time::sleep(DEFAULT_REDRAW_WINDOW * 2 + Duration::from_millis(50));
+        settle().await;
+        let before = *draws.lock().unwrap();
+
+        cast!(actor, AppMessage::HandleEvent(Event::FocusLost)).unwrap();
+        app.get_counter().await.unwrap();
+        // Drawn dimmed once, then held.
+        assert_eq!(*draws.lock().unwrap(), before + 1);
+        app.set_title("while away".to_string()).await.unwrap();
+        settle().await;
+        app.get_counter().await.unwrap();
+        assert_eq!(*draws.lock().unwrap(), before + 1);
+
+        cast!(actor, AppMessage::HandleEvent(Event::FocusGained)).unwrap();
+        settle().await;
+        app.get_counter().await.unwrap();
+        assert!(*draws.lock().unwrap() > before + 1);
+        assert_eq!(app.snapshot().await.unwrap().title, "while away");
+        actor.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn losing_focus_dims_everything_until_it_comes_back() {
+        let mut app = Harness::new().await;
+        let dim = |buf: &Buffer, (x, y): (u16, u16)| buf[(x, y)].modifier.contains(Modifier::DIM);
+
+        app.state.handle_event(Event::FocusLost).await.unwrap();
+        let buf = app.buffer();
+        let undimmed = buf
+            .area
+            .positions()
+            .filter(|pos| !dim(&buf, (pos.x, pos.y)))
+            .collect::<Vec<_>>();
+        assert!(undimmed.is_empty(), "{:?}", undimmed);
+
+        app.state.handle_event(Event::FocusGained).await.unwrap();
+        let buf = app.buffer();
+        let (x, y) = find(&buf, "Value: 0").unwrap();
+        assert!((x..x + 8).all(|x| !dim(&buf, (x, y))));
+        assert!(!dim(&buf, (0, 0)));
+    }
+
+    #[tokio::test]
+    async fn a_burst_within_the_redraw_window_draws_once() {
+        assert_eq!(draws_for_a_burst(Duration::from_millis(200)).await, 1);
+    }
+
+    #[tokio::test]
+    async fn no_redraw_window_draws_every_change() {
+        assert_eq!(draws_for_a_burst(Duration::ZERO).await, 4);
+    }
+
+    /// A test terminal that takes twice the frame budget over each draw.
+    #[derive(Debug)]
+    struct SlowTerminal(Box<dyn DrawTarget>);
+
+    impl DrawTarget for SlowTerminal {
+        fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+            std::thread::sleep(FRAME_BUDGET * 2);
+            self.0.draw(render)
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            self.0.clear()
+        }
+
+        fn autoresize(&mut self) -> io::Result<()> {
+            self.0.autoresize()
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            self.0.size()
+        }
+    }
+
+    #[tokio::test]
+    async fn a_slow_frame_is_warned_about() {
+        let (logs, _logging) = Logs::capture();
+        let mut app =
+            Harness::with_args(|args| args.tui = Box::new(SlowTerminal(test_terminal(80, 20))))
+                .await;
+        app.state.draw_now().await.unwrap();
+        let logs = logs.text();
+        let warning = logs
+            .lines()
+            .find(|line| line.contains("WARN") && line.contains("Frame took"))
+            .unwrap_or_else(|| panic!("no warning in:\n{}", logs));
+        assert!(
+            warning.contains("over the 16ms budget (80x20)"),
+            "{}",
+            warning
+        );
+    }
+
+    /// A harness drawing to a [`FlakyTerminal`], with its clear count.
+    async fn flaky_harness(failures: usize) -> (Harness, Arc<Mutex<usize>>) {
+        let clears = Arc::new(Mutex::new(0));
+        let terminal = FlakyTerminal {
+            inner: test_terminal(80, 20),
+            failures: Arc::new(Mutex::new(failures)),
+            clears: clears.clone(),
+        };
+        let app = Harness::with_args(|args| args.tui = Box::new(terminal)).await;
+        (app, clears)
+    }
+
+    #[tokio::test]
+    async fn a_failed_draw_is_cleared_and_retried() {
+        let (mut app, clears) = flaky_harness(1).await;
+        app.state.draw_now().await.unwrap();
+        assert_eq!(*clears.lock().unwrap(), 1);
+        assert!(!app.state.dirty);
+        assert!(app.state.tui.is_some());
+    }
+
+    #[tokio::test]
+    async fn a_draw_that_fails_twice_gives_up() {
+        let (mut app, clears) = flaky_harness(2).await;
+        assert!(app.state.draw_now().await.is_err());
+        assert_eq!(*clears.lock().unwrap(), 1);
+        assert!(app.state.dirty);
+        // The terminal is kept for the next draw, which works.
+        app.state.draw_now().await.unwrap();
+        assert!(!app.state.dirty);
+    }
+
+    #[tokio::test]
+    async fn waiting_for_exit_ends_when_the_app_fails() {
+        let app = Harness::new().await;
+        let failures = Arc::new(Mutex::new(0));
+        let mut args = test_args(&app.counter);
+        args.tui = Box::new(FlakyTerminal {
+            inner: test_terminal(80, 20),
+            failures: failures.clone(),
+            clears: Arc::new(Mutex::new(0)),
+        });
+        let (actor, handle) = Actor::spawn(None, App, args).await.unwrap();
+        // As `main` watches the app.
+        let (shutdown, stopped) = watch::channel(false);
+        tokio::spawn(async move {
+            let _ = handle.await;
+            let _ = shutdown.send(true);
+        });
+        let app = AppHandle::new(actor.clone());
+        let waiting = tokio::spawn({
+            let app = app.clone();
+            async move { app.wait_for_exit(stopped).await }
+        });
+
+        // A draw that fails even on its retry fails the handler.
+        *failures.lock().unwrap() = 2;
+        app.resize().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("still waiting on a failed app")
+            .unwrap()
+            .unwrap();
+        assert_eq!(actor.get_status(), ractor::ActorStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn waiting_for_exit_ends_when_the_app_is_stopped() {
+        let harness = Harness::new().await;
+        let (actor, handle) = Actor::spawn(None, App, test_args(&harness.counter))
+            .await
+            .unwrap();
+        let (shutdown, stopped) = watch::channel(false);
+        let watching = tokio::spawn(async move {
+            let _ = handle.await;
+            let _ = shutdown.send(true);
+        });
+        let app = AppHandle::new(actor.clone());
+        let waiting = tokio::spawn({
+            let app = app.clone();
+            let stopped = stopped.clone();
+            async move { app.wait_for_exit(stopped).await }
+        });
+
+        // Stopped from outside, never having been asked to exit.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        actor.stop(None);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("still waiting on a stopped app")
+            .unwrap()
+            .unwrap();
+        watching.await.unwrap();
+
+        // Once it's gone, waiting returns at once.
+        tokio::time::timeout(Duration::from_secs(1), app.wait_for_exit(stopped))
+            .await
+            .expect("waited on a stopped app")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn the_app_counts_each_kind_of_message() {
+        let harness = Harness::new().await;
+        let (actor, handle) = Actor::spawn(None, App, test_args(&harness.counter))
+            .await
+            .unwrap();
+        let app = AppHandle::new(actor.clone());
+
+        app.set_title("one".to_string()).await.unwrap();
+        app.set_title("two".to_string()).await.unwrap();
+        app.get_counter().await.unwrap();
+        let counts = app.message_counts().await.unwrap();
+        assert_eq!(counts.get("SetTitle"), 2);
+        assert_eq!(counts.get("GetCounter"), 1);
+        // Including the one asking.
+        assert_eq!(counts.get("MessageCounts"), 1);
+        assert_eq!(counts.get("Quit"), 0);
+        actor.stop(None);
+        handle.await.unwrap();
+    }
+
+    /// `buf` with everything inside its outer border blanked.
+    fn border_of(buf: &Buffer) -> Buffer {
+        let mut border = buf.clone();
+        let inner = buf.area.inner(ratatui::layout::Margin::new(1, 1));
+        for pos in inner.positions() {
+            border[pos] = Cell::default();
+        }
+        border
+    }
+
+    /// The row the value is drawn on, aligned as `align`.
+    async fn value_row(align: VerticalAlign) -> u16 {
+        let app = Harness::with_args(|args| args.align = align).await;
+        let (_, y) = find(&app.buffer(), "Value: 0").expect("no value drawn");
+        y
+    }
+
+    #[tokio::test]
+    async fn the_title_tags_each_mode_while_it_lasts() {
+        let mut app = Harness::new().await;
+        let title = |app: &Harness| line_text(&app.state.build_title());
+        let plain = title(&app);
+        assert!(!plain.contains('['), "{}", plain);
+
+        app.state.paused = Some(PauseReason::UserRequested);
+        assert_eq!(title(&app), format!("{}[paused] ", plain));
+        app.state.recording = true;
+        assert_eq!(title(&app), format!("{}[paused] [rec] ", plain));
+        let rec = app.state.build_title().spans.pop().unwrap();
+        assert_eq!(rec.style, Style::new().red());
+        app.state.demo = Some(Demo::start(Instant::now()));
+        assert_eq!(title(&app), format!("{}[paused] [rec] [auto] ", plain));
+
+        app.state.paused = None;
+        assert_eq!(title(&app), format!("{}[rec] [auto] ", plain));
+        app.state.recording = false;
+        assert_eq!(title(&app), format!("{}[auto] ", plain));
+        app.state.demo = None;
+        assert_eq!(title(&app), plain);
+    }
+
+    #[tokio::test]
+    async fn top_alignment_puts_the_value_first() {
+        assert_eq!(value_row(VerticalAlign::Top).await, 1);
+    }
+
+    #[tokio::test]
+    async fn center_alignment_puts_the_text_in_the_middle() {
+        assert_eq!(value_row(VerticalAlign::Center).await, 8);
+    }
+
+    #[tokio::test]
+    async fn bottom_alignment_puts_the_text_last() {
+        assert_eq!(value_row(VerticalAlign::Bottom).await, 15);
+    }
+
+    #[tokio::test]
+    async fn a_new_value_draws_over_the_cached_frame() {
+        let mut app = Harness::new().await;
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| app.state.draw(frame)).unwrap();
+        let before = terminal.backend().buffer().clone();
+        let cached = app.state.frame_cache.as_ref().unwrap().buffer.clone();
+
+        let (logs, _logging) = Logs::capture();
+        app.state
+            .apply_update(5, "test".to_string(), "test".to_string());
+        terminal.draw(|frame| app.state.draw(frame)).unwrap();
+        let after = terminal.backend().buffer().clone();
+        assert!(!logs.text().contains("Rendering frame"), "{}", logs.text());
+
+        assert!(diff_buffers(&before, &after).is_some());
+        if let Some(diff) = diff_buffers(&border_of(&before), &border_of(&after)) {
+            panic!("{}", diff);
+        }
+        if let Some(diff) = diff_buffers(&cached, &app.state.frame_cache.as_ref().unwrap().buffer) {
+            panic!("{}", diff);
+        }
+        assert!(find(&after, "Value: 5").is_some());
+    }
+
+    #[tokio::test]
+    async fn increment_now_answers_with_each_new_value() {
+        let harness = Harness::new().await;
+        let (actor, handle) = Actor::spawn(None, App, test_args(&harness.counter))
+            .await
+            .unwrap();
+        let app = AppHandle::new(actor.clone());
+
+        assert_eq!(app.increment_now().await.unwrap(), 1);
+        assert_eq!(app.increment_now().await.unwrap(), 2);
+        assert_eq!(app.get_counter().await.unwrap(), 2);
+        actor.stop(None);
+        handle.await.unwrap();
+        // Applied by the app alone, without waiting on the counter.
+        let received = harness.received().await;
+        assert!(
+            received
+                .iter()
+                .all(|message| message.starts_with("CounterMessage::AppReady")),
+            "{:?}",
+            received
+        );
+    }
+
+    #[tokio::test]
+    async fn a_counter_swapped_in_gets_the_work() {
+        let first = Harness::new().await;
+        let second = Harness::new().await;
+        // Neither counter is registered under a name, so nothing sent to
+        // them can have gone by way of the registry.
+        assert!(first.counter.get_name().is_none());
+        assert!(second.counter.get_name().is_none());
+        let (actor, handle) = Actor::spawn(None, App, test_args(&first.counter))
+            .await
+            .unwrap();
+        let app = AppHandle::new(actor.clone());
+
+        cast!(actor, AppMessage::SetCounterRef(second.counter.clone())).unwrap();
+        for _ in 0..2 {
+            app.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE))
+                .await
+                .unwrap();
+            tokio::time::sleep(INCREMENT_WINDOW * 2).await;
+        }
+        actor.stop(None);
+        handle.await.unwrap();
+
+        let first = first.received().await;
+        assert!(
+            first
+                .iter()
+                .all(|message| message.starts_with("CounterMessage::AppReady")),
+            "{:?}",
+            first
+        );
+        let second = second.received().await;
+        assert!(
+            second[0].starts_with("CounterMessage::AppReady"),
+            "{:?}",
+            second
+        );
+        assert_eq!(
+            second
+                .iter()
+                .filter(|message| message.starts_with("CounterMessage::IncrementBy"))
+                .count(),
+            2,
+            "{:?}",
+            second
+        );
+    }
+
+    #[tokio::test]
+    async fn a_terminal_that_never_answers_times_out_the_spawn() {
+        let app = Harness::new().await;
+        let mut args = test_args(&app.counter);
+        args.tui = Box::new(UnresponsiveTerminal);
+        args.startup_timeout = Duration::from_millis(50);
+
+        let started = Instant::now();
+        let err = match Actor::spawn(None, App, args).await {
+            Err(ractor::SpawnErr::StartupFailed(err)) => err,
+            Err(err) => panic!("expected a failed startup, got {}", err),
+            Ok(_) => panic!("the app started"),
+        };
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(
+            err.downcast_ref::<StartupTimeoutError>(),
+            Some(&StartupTimeoutError {
+                after: Duration::from_millis(50)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn holding_right_speeds_up_the_step() {
+        let mut app = Harness::new().await;
+        let start = Instant::now();
+        // A press every 100ms, as a held key repeats, for three and a half
+        // seconds.
+        let steps: Vec<u8> = (0..36)
+            .map(|i| {
+                app.state
+                    .increment_step(start + Duration::from_millis(100) * i)
+            })
+            .collect();
+        assert!(
+            steps.windows(2).all(|pair| pair[0] <= pair[1]),
+            "{:?}",
+            steps
+        );
+        assert_eq!([steps[0], steps[10], steps[20], steps[30]], [1, 2, 5, 10]);
+
+        // A pause longer than a repeat starts over.
+        let later = start + Duration::from_secs(4) + HOLD_TIMEOUT;
+        assert_eq!(app.state.increment_step(later), 1);
+        // So does letting go, however long the key was held.
+        for i in 1..=12 {
+            app.state
+                .increment_step(later + Duration::from_millis(100) * i);
+        }
+        app.state.release_hold();
+        assert_eq!(
+            app.state
+                .increment_step(later + Duration::from_millis(1300)),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn the_stats_panel_tracks_min_max_and_average() {
+        let mut app = Harness::new().await;
+        assert_eq!(app.state.value_stats.mean(), None);
+        for value in [5, 2, 9, 4] {
+            app.state
+                .apply_update(value, "test".to_string(), "test".to_string());
+        }
+        assert_eq!(app.state.value_stats.min, 2);
+        assert_eq!(app.state.value_stats.max, 9);
+        assert_eq!(app.state.value_stats.mean(), Some(5.0));
+
+        app.state.add_panel(Panel::StatsPanel);
+        let screen = app.screen();
+        assert!(screen.contains("Min/max: 2/9"), "{}", screen);
+        assert!(screen.contains("Avg: 5.0"), "{}", screen);
+    }
+
+    #[tokio::test]
+    async fn a_burst_is_averaged_over_the_rate_window() {
+        let mut app = Harness::new().await;
+        let start = Instant::now();
+        // Twenty updates in the first second.
+        for i in 0..20 {
+            app.state
+                .record_update(start + Duration::from_millis(50) * i);
+        }
+        assert!(
+            (app.state.rate() - 2.0).abs() < 0.01,
+            "{}",
+            app.state.rate()
+        );
+
+        // Half a second past the window, the first half of the burst is gone.
+        assert!(
+            app.state
+                .expire_updates(start + RATE_WINDOW + Duration::from_millis(500))
+        );
+        assert!(
+            (app.state.rate() - 1.0).abs() < 0.01,
+            "{}",
+            app.state.rate()
+        );
+        assert!(
+            !app.state
+                .expire_updates(start + RATE_WINDOW + Duration::from_millis(500))
+        );
+    }
+
+    #[tokio::test]
+    async fn ticks_redraw_the_rate_as_updates_age_out() {
+        let mut app = Harness::new().await;
+        let old = Instant::now()
+            .checked_sub(RATE_WINDOW + Duration::from_secs(1))
+            .unwrap();
+        app.state.record_update(old);
+        app.state.dirty = false;
+
+        app.state.tick().await.unwrap();
+        assert_eq!(app.state.rate(), 0.0);
+        assert!(app.state.dirty);
+    }
+
+    #[tokio::test]
+    async fn the_status_bar_shows_a_queue_until_it_drains() {
+        let mut app = Harness::new().await;
+        assert!(!app.screen().contains("queue:"));
+        for depth in [3, 2, 1] {
+            app.state.queue_depth = depth;
+            assert!(
+                app.screen().contains(&format!("queue: {}", depth)),
+                "{}",
+                app.screen()
+            );
+        }
+        app.state.queue_depth = 0;
+        assert!(!app.screen().contains("queue:"));
+    }
+
+    /// A harness with counters registered under `names`, all at 0 and
+    /// already drawn.
+    async fn harness_with_counters(names: &[&str]) -> Harness {
+        let mut app = Harness::new().await;
+        for name in names {
+            app.state.counters.push(NamedCounter {
+                name: name.to_string(),
+                value: 0,
+                handle: CounterHandle::new(app.counter.clone()),
+            });
+        }
+        app.state.draw_now().await.unwrap();
+        app
+    }
+
+    fn counter_values(app: &Harness) -> Vec<u8> {
+        app.state
+            .counters
+            .iter()
+            .map(|counter| counter.value)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn a_batch_sets_every_counter_for_one_draw() {
+        let mut app = harness_with_counters(&["a", "b", "c"]).await;
+
+        app.state.batch_update(vec![
+            ("a".to_string(), 1),
+            ("c".to_string(), 3),
+            ("b".to_string(), 2),
+        ]);
+        assert_eq!(counter_values(&app), [1, 2, 3]);
+        assert!(app.state.dirty);
+        app.state.draw_now().await.unwrap();
+        assert!(!app.state.dirty);
+    }
+
+    #[tokio::test]
+    async fn a_batch_with_an_unknown_name_sets_nothing() {
+        let mut app = harness_with_counters(&["a", "b", "c"]).await;
+
+        app.state
+            .batch_update(vec![("a".to_string(), 1), ("nope".to_string(), 2)]);
+        assert_eq!(counter_values(&app), [0, 0, 0]);
+        assert!(
+            app.screen().contains("Batch not applied; unknown: nope"),
+            "{}",
+            app.screen()
+        );
+    }
+
+    /// The text of `line`, without its styles.
+    fn line_text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn the_instructions_list_every_binding_shown_there() {
+        let bindings = [
+            KeyBinding::new("<Left>", "Decrement").shown_as("Decrement"),
+            KeyBinding::new("<Home>", "Jump to 0"),
+            KeyBinding::new("<X>", "Something new").shown_as("New"),
+        ];
+        let line = render_instructions(&bindings, &Strings::default(), Color::Blue);
+        assert_eq!(line_text(&line), " Decrement <Left> New <X> ");
+        assert_eq!(line.spans[1].style.fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn the_instructions_use_the_translated_names() {
+        let strings = Strings {
+            quit: "Beenden".to_string(),
+            ..Strings::default()
+        };
+        let bindings = [KeyBinding::new("<Q>", "Quit").shown_as("Quit")];
+        let line = render_instructions(&bindings, &strings, Color::Blue);
+        assert_eq!(line_text(&line), " Beenden <Q> ");
+    }
+
+    #[tokio::test]
+    async fn ascii_borders_have_plus_corners() {
+        let mut app = Harness::with_args(|args| args.ascii_borders = true).await;
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| app.state.draw(frame)).unwrap();
+        let buf = terminal.backend().buffer();
+
+        let (right, bottom) = (buf.area.width - 1, buf.area.height - 1);
+        for corner in [(0, 0), (right, 0), (0, bottom), (right, bottom)] {
+            assert_eq!(buf[corner].symbol(), "+", "at {:?}", corner);
+        }
+        // The sparkline keeps its blocks; only borders are swapped.
+        assert!(
+            buf.content
+                .iter()
+                .all(|cell| ascii_border(cell.symbol()).is_none())
+        );
+        // The title still fits in the top border.
+        let top: String = (0..buf.area.width).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(top.contains(" Counter App Tutorial "), "{}", top);
+    }
+
+    #[tokio::test]
+    async fn up_and_down_scroll_the_focused_history() {
+        let mut app = Harness::new().await;
+        for value in 1..=5 {
+            app.state.set_counter(ReplayOp::Update(value));
+        }
+        assert_eq!(app.state.focused_panel, Panel::CounterPanel.id());
+
+        app.press(KeyCode::Up).await;
+        app.press(KeyCode::Up).await;
+        assert_eq!(app.state.history_scroll, 2);
+        app.press(KeyCode::Down).await;
+        assert_eq!(app.state.history_scroll, 1);
+        assert_eq!(app.state.log_scroll, 0);
+        assert_eq!(app.state.counter, 5);
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn up_scrolls_the_log_once_it_has_focus() {
+        let mut app = Harness::new().await;
+        for value in 1..=5 {
+            app.state.set_counter(ReplayOp::Update(value));
+            app.state.push_log(format!("entry {}", value));
+        }
+        app.state.add_panel(Panel::LogPanel);
+        app.press(KeyCode::BackTab).await;
+        assert_eq!(app.state.focused_panel, Panel::LogPanel.id());
+
+        app.press(KeyCode::Up).await;
+        assert_eq!(app.state.log_scroll, 1);
+        assert_eq!(app.state.history_scroll, 0);
+    }
+
+    #[tokio::test]
+    async fn toasts_show_until_their_time_is_up() {
+        let mut app = Harness::new().await;
+        app.state
+            .toasts
+            .push("first".to_string(), Style::new(), Duration::from_millis(50));
+        app.state.toasts.push(
+            "second".to_string(),
+            Style::new(),
+            Duration::from_millis(150),
+        );
+        let screen = app.screen();
+        assert!(
+            screen.contains("first") && screen.contains("second"),
+            "{}",
+            screen
+        );
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        app.state.tick().await.unwrap();
+        let screen = app.screen();
+        assert!(!screen.contains("first"), "{}", screen);
+        assert!(screen.contains("second"), "{}", screen);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        app.state.dirty = false;
+        app.state.tick().await.unwrap();
+        assert!(app.state.toasts.is_empty());
+        assert!(app.state.dirty);
+        assert!(!app.screen().contains("second"));
+    }
+
+    /// What's shown after each tick until the value's reached, up to ten.
+    async fn frames(app: &mut Harness) -> Vec<u8> {
+        let mut shown = Vec::new();
+        while app.state.animating_to.is_some() && shown.len() < 10 {
+            app.state.tick().await.unwrap();
+            shown.push(app.state.displayed);
+        }
+        shown
+    }
+
+    #[tokio::test]
+    async fn a_jump_counts_to_the_value_over_a_few_ticks() {
+        let mut app = Harness::with_args(|args| args.reduce_motion = false).await;
+        app.state.set_counter(ReplayOp::Update(200));
+        assert_eq!(app.state.displayed, 0);
+        assert_eq!(frames(&mut app).await, [50, 100, 150, 200]);
+        assert!(app.screen().contains("Value: 200"));
+
+        app.state.set_counter(ReplayOp::Update(190));
+        // Steps are rounded up, so it never takes more than the four.
+        assert_eq!(frames(&mut app).await, [197, 194, 191, 190]);
+    }
+
+    #[tokio::test]
+    async fn reduced_motion_jumps_straight_to_the_value() {
+        let mut app = Harness::new().await;
+        app.state.set_counter(ReplayOp::Update(200));
+        assert_eq!(app.state.displayed, 200);
+        assert_eq!(app.state.animating_to, None);
+    }
+
+    /// Two frames of a task in progress with a jump to show, a progress
+    /// report and a tick apart.
+    async fn consecutive_frames(reduce_motion: bool) -> (Buffer, Buffer) {
+        let mut app = Harness::with_args(|args| args.reduce_motion = reduce_motion).await;
+        app.state.set_counter(ReplayOp::Update(200));
+        app.state.progress = Some(40);
+        let first = app.buffer();
+        // As an `UpdateProgress` moves the spinner on.
+        app.state.spinner_frame += 1;
+        app.state.tick().await.unwrap();
+        (first, app.buffer())
+    }
+
+    #[tokio::test]
+    async fn reduced_motion_draws_the_same_frame_twice() {
+        let (first, second) = consecutive_frames(true).await;
+        if let Some(diff) = diff_buffers(&first, &second) {
+            panic!("{}", diff);
+        }
+        assert!(
+            first
+                .content
+                .iter()
+                .all(|cell| !cell.modifier.contains(Modifier::SLOW_BLINK))
+        );
+    }
+
+    #[tokio::test]
+    async fn motion_changes_the_frame() {
+        let (first, second) = consecutive_frames(false).await;
+        assert!(diff_buffers(&first, &second).is_some());
+    }
+
+    #[tokio::test]
+    async fn ticks_change_nothing_while_frozen() {
+        let mut app = Harness::with_args(|args| args.reduce_motion = false).await;
+        // Both left to ticks: counting up to the value, and the toast going.
+        app.state.set_counter(ReplayOp::Update(200));
+        app.state.toasts.push(
+            "gone by the next tick".to_string(),
+            Style::new(),
+            Duration::ZERO,
+        );
+        app.press(KeyCode::F(5)).await;
+        let frozen = app.screen();
+        assert!(frozen.contains("gone by the next tick"), "{}", frozen);
+
+        for _ in 0..3 {
+            app.state.tick().await.unwrap();
+        }
+        assert_eq!(app.screen(), frozen);
+        // Keys still show.
+        app.press(KeyCode::Char('2')).await;
+        assert_ne!(app.screen(), frozen);
+        assert!(app.screen().contains(" Log "), "{}", app.screen());
+
+        app.press(KeyCode::F(5)).await;
+        let displayed = app.state.displayed;
+        app.state.tick().await.unwrap();
+        assert!(app.state.displayed > displayed);
+        assert!(!app.screen().contains("gone by the next tick"));
+    }
+
+    /// The bottom border, where the instructions go.
+    fn instructions_row(app: &Harness) -> String {
+        app.screen().lines().last().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn the_instructions_follow_the_mode() {
+        let mut app = Harness::new().await;
+        let normal = instructions_row(&app);
+        assert!(normal.contains(" Set <=> "), "{}", normal);
+
+        app.press(KeyCode::Char('=')).await;
+        app.press(KeyCode::Char('4')).await;
+        let input = instructions_row(&app);
+        assert!(
+            input.contains(" Value: 4_ Commit <Enter> Cancel <Esc> "),
+            "{}",
+            input
+        );
+        assert!(!input.contains("Set <=>"), "{}", input);
+
+        app.press(KeyCode::Esc).await;
+        app.press(KeyCode::Char('?')).await;
+        let help = instructions_row(&app);
+        assert!(help.contains(" Close <Esc> "), "{}", help);
+
+        app.press(KeyCode::Esc).await;
+        assert_eq!(instructions_row(&app), normal);
+    }
+
+    #[tokio::test]
+    async fn q_exits_without_touching_the_counter() {
+        let mut app = Harness::new().await;
+        app.press(KeyCode::Char('q')).await;
+        assert!(app.state.exit);
+        assert_eq!(app.state.counter, 0);
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_modal_swallows_keys_until_dismissed() {
+        let mut app = Harness::new().await;
+        app.state.set_mode(DisplayMode::Modal(Modal::new(
+            "Note".to_string(),
+            "Read me".to_string(),
+            vec!["Back".to_string(), "Next".to_string()],
+        )));
+        // Right picks the next button instead of incrementing.
+        app.press(KeyCode::Right).await;
+        let DisplayMode::Modal(modal) = &app.state.mode else {
+            panic!("the modal closed");
+        };
+        assert_eq!(modal.selected(), "Next");
+        app.press(KeyCode::Char('+')).await;
+        app.press(KeyCode::Esc).await;
+        assert_eq!(app.state.mode, DisplayMode::Normal);
+
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(app.state.counter, 0);
+        assert!(app.state.toasts.is_empty());
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn left_saturates_at_zero() {
+        let mut app = Harness::new().await;
+        app.press(KeyCode::Left).await;
+        assert_eq!(app.state.counter, 0);
+        assert!(!app.state.toasts.is_empty());
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn right_at_255_flashes_instead_of_incrementing() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(u8::MAX, "test".to_string(), "test".to_string());
+        app.press(KeyCode::Right).await;
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(app.state.counter, u8::MAX);
+        assert!(app.screen().contains("Already at 255"), "{}", app.screen());
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn left_asks_the_counter_to_decrement() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(5, "test".to_string(), "test".to_string());
+        app.press(KeyCode::Left).await;
+        assert_eq!(app.state.counter, 5);
+        assert_eq!(app.state.progress, Some(0));
+        assert_eq!(app.received().await, ["CounterMessage::Decrement(5)"]);
+    }
+
+    #[tokio::test]
+    async fn left_in_sync_mode_decrements_at_once() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(5, "test".to_string(), "test".to_string());
+        app.press(KeyCode::Char('s')).await;
+        app.press(KeyCode::Left).await;
+        assert_eq!(app.state.counter, 4);
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn right_asks_the_counter_to_increment() {
+        let mut app = Harness::new().await;
+        app.press(KeyCode::Right).await;
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(app.state.counter, 0);
+        assert_eq!(app.state.progress, Some(0));
+        assert_eq!(
+            app.received().await,
+            [r#"CounterMessage::IncrementBy(0, 1, "increment", "app")"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn restoring_a_snapshot_changes_nothing() {
+        let modes = [
+            DisplayMode::Normal,
+            DisplayMode::Input("4".to_string()),
+            DisplayMode::WatchInput(String::new()),
+            DisplayMode::Help,
+            DisplayMode::Palette(Palette::default()),
+        ];
+        // Histories from empty to past capacity, over values from both ends.
+        for (i, mode) in modes.into_iter().enumerate() {
+            for len in [0, 1, 7, HISTORY_CAPACITY, HISTORY_CAPACITY + 5] {
+                let mut app = Harness::new().await;
+                for step in 0..len {
+                    let value = (step * 37 + i * 11) as u8;
+                    app.state
+                        .apply_update(value, "test".to_string(), "test".to_string());
+                }
+                app.state.title = format!("title {} {}", i, len);
+                app.state.exit = len % 2 == 1;
+                app.state.mode = mode.clone();
+                let snap = app.state.snapshot();
+                let screen = app.screen();
+
+                app.state.restore(snap.clone());
+                assert_eq!(app.state.snapshot(), snap, "{:?}, {} values", mode, len);
+                assert_eq!(app.screen(), screen, "{:?}, {} values", mode, len);
+
+                let mut fresh = Harness::new().await;
+                fresh.state.restore(snap.clone());
+                assert_eq!(fresh.state.snapshot(), snap, "{:?}, {} values", mode, len);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_keys_change_nothing() {
+        let mut app = Harness::new().await;
+        let before = app.state.snapshot();
+        app.press(KeyCode::Char('z')).await;
+        app.press(KeyCode::F(12)).await;
+        assert_eq!(app.state.snapshot(), before);
+        assert!(!app.state.exit);
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn resetting_a_small_value_is_immediate() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(5, "test".to_string(), "test".to_string());
+        app.run_palette("reset").await;
+        assert_eq!(app.state.mode, DisplayMode::Normal);
+        assert_eq!(app.received().await, ["CounterMessage::Reset"]);
+    }
+
+    #[tokio::test]
+    async fn resetting_a_large_value_asks_first() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(200, "test".to_string(), "test".to_string());
+        app.run_palette("reset").await;
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        // Cancel is selected to begin with.
+        app.press(KeyCode::Enter).await;
+        assert_eq!(app.state.mode, DisplayMode::Normal);
+        app.run_palette("reset").await;
+        app.press(KeyCode::Right).await;
+        app.press(KeyCode::Enter).await;
+        assert_eq!(app.state.mode, DisplayMode::Normal);
+        assert_eq!(app.received().await, ["CounterMessage::Reset"]);
+    }
+
+    #[tokio::test]
+    async fn presses_in_a_burst_go_as_one_increment() {
+        let mut app = Harness::new().await;
+        for _ in 0..5 {
+            app.press(KeyCode::Right).await;
+            // Not held, so each press is a step of one.
+            app.state.release_hold();
+        }
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(
+            app.received().await,
+            [r#"CounterMessage::IncrementBy(0, 5, "increment", "app")"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_watch_fires_when_the_counter_arrives() {
+        let mut app = Harness::new().await;
+        for code in [KeyCode::Char('w'), KeyCode::Char('3'), KeyCode::Enter] {
+            app.press(code).await;
+        }
+        assert_eq!(app.state.watch_target, Some(3));
+        for value in [1, 2, 3] {
+            app.state
+                .apply_update(value, "test".to_string(), "test".to_string());
+        }
+        assert_eq!(
+            app.state.log.iter().filter(|e| *e == "Reached 3").count(),
+            1
+        );
+        // Leaving the prompt empty stops watching.
+        app.press(KeyCode::Char('w')).await;
+        app.press(KeyCode::Enter).await;
+        assert_eq!(app.state.watch_target, None);
+    }
+
+    #[tokio::test]
+    async fn declining_to_cancel_a_long_task_keeps_it() {
+        let mut app = Harness::new().await;
+        app.state.cancel_confirm_above = Some(80);
+        app.state.progress = Some(90);
+        app.press(KeyCode::Right).await;
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        app.press(KeyCode::Enter).await;
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(app.state.progress, Some(90));
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn confirming_the_cancel_goes_ahead() {
+        let mut app = Harness::new().await;
+        app.state.cancel_confirm_above = Some(80);
+        app.state.progress = Some(90);
+        app.press(KeyCode::Right).await;
+        app.press(KeyCode::Right).await;
+        app.press(KeyCode::Enter).await;
+        assert_eq!(app.state.mode, DisplayMode::Normal);
+        app.state.flush_increments().await.unwrap();
+        assert_eq!(
+            app.received().await,
+            [r#"CounterMessage::IncrementBy(0, 1, "increment", "app")"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn f3_shows_frame_timings() {
+        let mut app = Harness::new().await;
+        app.press(KeyCode::F(3)).await;
+        assert!(app.state.profiler_overlay);
+        // Real draws take however long they take, so the frames are recorded
+        // as `draw_now` would record them.
+        for ms in [2, 4, 6] {
+            app.state.frame_timings.record(Duration::from_millis(ms));
+        }
+        assert_eq!(
+            app.state.frame_timings.last(),
+            Some(Duration::from_millis(6))
+        );
+        assert_eq!(
+            app.state.frame_timings.average(),
+            Some(Duration::from_millis(4))
+        );
+        let screen = app.screen();
+        assert!(screen.contains("Last  6.0ms"), "{}", screen);
+        assert!(screen.contains("Avg   4.0ms"), "{}", screen);
+        assert!(screen.contains("Draws 3"), "{}", screen);
+
+        app.press(KeyCode::F(3)).await;
+        assert!(!app.state.profiler_overlay);
+    }
+
+    #[tokio::test]
+    async fn an_injected_string_set_replaces_the_english() {
+        let english = Harness::new().await.screen();
+        assert!(english.contains("Counter App Tutorial"), "{}", english);
+
+        let french = Strings {
+            title: "Tutoriel du compteur".to_string(),
+            value: "Valeur".to_string(),
+            quit: "Quitter".to_string(),
+            ..Strings::default()
+        };
+        let screen = Harness::with_args(|args| args.strings = french)
+            .await
+            .screen();
+        let title = screen.lines().next().unwrap();
+        assert!(title.contains(" Tutoriel du compteur "), "{}", screen);
+        assert!(!screen.contains("Counter App Tutorial"), "{}", screen);
+        assert!(screen.contains("Valeur: 0"), "{}", screen);
+        assert!(screen.contains(" Quitter <Q>"), "{}", screen);
+    }
+
+    #[tokio::test]
+    async fn panels_overlays_and_dialogs_use_the_string_set() {
+        let strings = "\
+            stats = \"Statistiques\"
+            min_max = \"Min/max FR\"
+            keys = \"Touches\"
+            close = \"Fermer\"
+            key \"<=>\" = \"Choisir une valeur\"
+            at_min = \"Déjà à 0\"
+            reset_prompt = \"Remettre {value} à zéro ?\"
+            cancel = \"Annuler\"
+            paused = \"EN PAUSE\"
+        "
+        .parse::<Strings>()
+        .unwrap();
+        let mut app = Harness::with_args(|args| args.strings = strings).await;
+        app.state
+            .apply_update(200, "test".to_string(), "test".to_string());
+
+        app.press(KeyCode::Char('3')).await;
+        let screen = app.screen();
+        assert!(screen.contains(" Statistiques "), "{}", screen);
+        assert!(screen.contains("Min/max FR: 200/200"), "{}", screen);
+
+        app.press(KeyCode::Char('?')).await;
+        let screen = app.screen();
+        assert!(screen.contains(" Touches "), "{}", screen);
+        assert!(screen.contains("<=> Choisir une valeur"), "{}", screen);
+        // Keys the set doesn't describe stay in English.
+        assert!(
+            screen.contains("<Tab> Focus the next counter"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains(" Fermer <Esc>"), "{}", screen);
+        app.press(KeyCode::Esc).await;
+
+        app.run_palette("reset").await;
+        let screen = app.screen();
+        assert!(screen.contains("Remettre 200 à zéro ?"), "{}", screen);
+        assert!(screen.contains(" Annuler "), "{}", screen);
+        app.press(KeyCode::Esc).await;
+
+        app.press(KeyCode::Char('p')).await;
+        assert!(app.screen().contains("EN PAUSE"), "{}", app.screen());
+        app.press(KeyCode::Char('p')).await;
+
+        app.state.set_counter(ReplayOp::Update(0));
+        app.press(KeyCode::Left).await;
+        assert!(app.screen().contains("Déjà à 0"), "{}", app.screen());
+    }
+
+    #[tokio::test]
+    async fn q_with_unsaved_changes_asks_first() {
+        let mut app = Harness::new().await;
+        app.state.set_counter(ReplayOp::Update(3));
+        app.press(KeyCode::Char('q')).await;
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        assert!(!app.state.exit);
+
+        for declined in [KeyCode::Char('n'), KeyCode::Esc] {
+            app.press(declined).await;
+            assert_eq!(app.state.mode, DisplayMode::Normal);
+            assert!(!app.state.exit);
+            app.press(KeyCode::Char('q')).await;
+        }
+        app.press(KeyCode::Char('y')).await;
+        assert!(app.state.exit);
+    }
+
+    #[tokio::test]
+    async fn palette_actions_check_what_their_keys_check() {
+        let mut app = Harness::new().await;
+        app.state
+            .apply_update(255, "test".to_string(), "test".to_string());
+        app.run_palette("increment").await;
+        app.state.flush_increments().await.unwrap();
+        assert!(app.screen().contains("Already at 255"), "{}", app.screen());
+
+        app.state.cancel_confirm_above = Some(80);
+        app.state.progress = Some(90);
+        app.run_palette("decrement").await;
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        app.press(KeyCode::Esc).await;
+        assert!(app.received().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn palette_and_script_quits_ask_first_too() {
+        let mut app = Harness::new().await;
+        app.state.set_counter(ReplayOp::Update(3));
+        app.run_palette("quit").await;
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        app.press(KeyCode::Esc).await;
+
+        app.state.run_script(ScriptCommand::Quit).await.unwrap();
+        assert!(matches!(app.state.mode, DisplayMode::Modal(_)));
+        assert!(!app.state.exit);
+    }
+
+    /// Where `text` first appears in `buf`, if it's all on one row.
+    fn find(buf: &Buffer, text: &str) -> Option<(u16, u16)> {
+        (0..buf.area.height).find_map(|y| {
+            let row: String = (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect();
+            // Every cell on screen here holds one character.
+            row.find(text).map(|i| (row[..i].chars().count() as u16, y))
+        })
+    }
+
+    #[tokio::test]
+    async fn a_theme_file_colors_the_value() {
+        let path =
+            std::env::temp_dir().join(format!("ratatui-ractor-theme-{}.toml", std::process::id()));
+        std::fs::write(&path, "# Orange values\nvalue = \"#ff8800\"\n").unwrap();
+        let theme = Theme::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut app = Harness::with_args(|args| args.theme = theme).await;
+        app.state.set_counter(ReplayOp::Update(7));
+
+        let buf = app.buffer();
+        let (x, y) = find(&buf, "Value: 7").unwrap();
+        assert_eq!(buf[(x + 7, y)].fg, Color::Rgb(0xff, 0x88, 0x00));
+    }
+
+    #[tokio::test]
+    async fn a_widget_draws_with_the_theme_it_is_given() {
+        let mut app = Harness::new().await;
+        app.state.set_counter(ReplayOp::Update(7));
+        let light = Theme {
+            value: Color::Black,
+            key: Color::DarkGray,
+            progress: Color::DarkGray,
+            sparkline: Color::Gray,
+        };
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+        AppWidget::new(&app.state)
+            .theme(&light)
+            .render(area, &mut buf);
+
+        let (x, y) = find(&buf, "Value: 7").unwrap();
+        assert_eq!(buf[(x + 7, y)].fg, Color::Black);
+        let (x, y) = find(&buf, "<Q>").unwrap();
+        assert_eq!(buf[(x, y)].fg, Color::DarkGray);
+        // The state's own theme is left as it was.
+        assert_eq!(app.state.theme, Theme::default());
+    }
+
+    #[tokio::test]
+    async fn the_sparkline_graphs_the_history() {
+        let mut app = Harness::new().await;
+        for value in [0, 1, 2, 1, 0] {
+            app.state.set_counter(ReplayOp::Update(value));
+        }
+        assert_eq!(app.state.sparkline_data(78), [0, 1, 2, 1, 0]);
+        // Only as many as fit, newest kept.
+        assert_eq!(app.state.sparkline_data(3), [2, 1, 0]);
+        app.state.history_scroll = 1;
+        assert_eq!(app.state.sparkline_data(78), [0, 1, 2, 1]);
+    }
+
+    /// The columns each panel's box spans, given by its corners on the
+    /// panels' top row.
+    fn panel_columns(app: &Harness) -> Vec<(u16, u16)> {
+        let buf = app.buffer();
+        let corners = |corner| {
+            (0..buf.area.width)
+                .filter(|&x| buf[(x, 1)].symbol() == corner)
+                .collect::<Vec<_>>()
+        };
+        corners("┌").into_iter().zip(corners("┐")).collect()
+    }
+
+    #[tokio::test]
+    async fn the_value_and_status_bar_stay_put() {
+        let app = Harness::new().await;
+        let buf = app.buffer();
+        // Centered on the first row inside the frame, with the status bar on
+        // the last.
+        assert_eq!(find(&buf, "Value: 0"), Some((36, 1)));
+        assert_eq!(find(&buf, " 1 panel: counter"), Some((1, 18)));
+        assert_eq!(find(&buf, " Decrement <Left>").map(|(_, y)| y), Some(19));
+    }
+
+    #[tokio::test]
+    async fn panels_share_the_width_equally() {
+        let mut app = Harness::new().await;
+        app.state.remove_panel(&Panel::CounterPanel.id());
+        for id in ["a", "b"] {
+            app.state.add_panel(Panel::Custom(PanelId(id.to_string())));
+        }
+        // The 78 columns inside the frame, halved.
+        assert_eq!(panel_columns(&app), [(1, 39), (40, 78)]);
+
+        app.state.add_panel(Panel::Custom(PanelId("c".to_string())));
+        assert_eq!(panel_columns(&app), [(1, 26), (27, 52), (53, 78)]);
+
+        app.state.remove_panel(&PanelId("b".to_string()));
+        assert_eq!(panel_columns(&app), [(1, 39), (40, 78)]);
+    }
+
+    #[tokio::test]
+    async fn rendering_into_part_of_a_buffer_leaves_the_rest() {
+        let app = Harness::new().await;
+        let mut buf = Buffer::filled(Rect::new(0, 0, 100, 30), Cell::new("x"));
+        let area = Rect::new(10, 5, 80, 20);
+        render_into(&app.state, area, &mut buf);
+
+        for position in buf.area.positions() {
+            if !area.contains(position) {
+                assert_eq!(buf[position].symbol(), "x", "at {:?}", position);
+            }
+        }
+        assert_eq!(buf[(10, 5)].symbol(), "┏");
+        assert_eq!(buf[(89, 24)].symbol(), "┛");
+        // Where it is at the origin, moved along by the area's corner.
+        let (x, y) = find(&app.buffer(), "Value: 0").unwrap();
+        assert_eq!(find(&buf, "Value: 0"), Some((x + 10, y + 5)));
+    }
+
+    #[tokio::test]
+    async fn a_wide_label_is_centered_by_its_width() {
+        let mut app = Harness::with_args(|args| args.strings.value = "値".to_string()).await;
+        app.state.set_counter(ReplayOp::Update(7));
+        let buf = app.buffer();
+
+        // "値: 7" is five columns wide, leaving 36 of the 78 inside the
+        // border to its left and 37 to its right, where counting bytes or
+        // characters would put it elsewhere.
+        let row = (0..buf.area.width)
+            .map(|x| buf[(x, 1)].symbol())
+            .collect::<Vec<_>>();
+        assert_eq!(row[36], " ");
+        assert_eq!(row[37..=41], ["値", " ", ":", " ", "7"]);
+        assert_eq!(row[42], " ");
+    }
+
+    #[tokio::test]
+    async fn exporting_saves_the_changes() {
+        let path =
+            std::env::temp_dir().join(format!("ratatui-ractor-export-{}.txt", std::process::id()));
+        let mut app = Harness::new().await;
+        for value in [3, 4] {
+            app.state.set_counter(ReplayOp::Update(value));
+        }
+        app.state.set_counter(ReplayOp::Reset);
+        app.state
+            .run_script(ScriptCommand::Export(path.clone()))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "3\n4\n0\n");
+        std::fs::remove_file(&path).unwrap();
+
+        app.press(KeyCode::Char('q')).await;
+        assert!(app.state.exit);
+    }
+
+    #[tokio::test]
+    async fn a_failed_export_leaves_the_changes_unsaved() {
+        let mut app = Harness::new().await;
+        app.state.set_counter(ReplayOp::Update(3));
+        let nowhere = std::env::temp_dir().join("ratatui-ractor-missing/export.txt");
+        app.state
+            .run_script(ScriptCommand::Export(nowhere))
+            .await
+            .unwrap();
+        assert!(app.state.unsaved_changes);
     }
 }
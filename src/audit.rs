@@ -0,0 +1,83 @@
+use ractor::{Actor, RpcReplyPort};
+
+/// Records which code path caused each counter update.
+pub struct AuditTrail;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditEntry {
+    pub value: u8,
+    pub label: String,
+    pub source: String,
+}
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum AuditMessage {
+    Record(AuditEntry),
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    GetEntries(RpcReplyPort<Vec<AuditEntry>>),
+}
+
+impl std::fmt::Display for AuditMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditMessage::Record(entry) => write!(
+                f,
+                "AuditMessage::Record({}, {:?}, {:?})",
+                entry.value, entry.label, entry.source
+            ),
+            AuditMessage::GetEntries(_) => write!(f, "AuditMessage::GetEntries"),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
+pub struct AuditTrailState {
+    entries: Vec<AuditEntry>,
+}
+
+impl Actor for AuditTrail {
+    type Msg = AuditMessage;
+
+    type State = AuditTrailState;
+
+    type Arguments = ();
+
+    async fn pre_start(
+        &self,
+        _myself: ractor::ActorRef<Self::Msg>,
+        _args: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        Ok(AuditTrailState::default())
+    }
+
+    async fn handle(
+        &self,
+        _myself: ractor::ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match message {
+            AuditMessage::Record(entry) => {
+                tracing::info!(
+                    "Audit: {} from {} ({})",
+                    entry.value,
+                    entry.source,
+                    entry.label
+                );
+                state.entries.push(entry);
+            }
+            AuditMessage::GetEntries(reply) => {
+                reply.send(state.entries.clone())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+/// What the terminal can do, as far as its environment says. There's no
+/// portable way to ask a terminal directly without risking a reply that
+/// never comes, so this goes by `TERM` and `COLORTERM` as most tools do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// 24-bit colors show as given rather than as the nearest of 256.
+    pub truecolor: bool,
+    pub mouse: bool,
+    /// Reports gaining and losing focus, rather than printing the request.
+    pub focus_reporting: bool,
+}
+
+impl Default for Capabilities {
+    /// Everything, as a modern terminal emulator has.
+    fn default() -> Self {
+        Self {
+            truecolor: true,
+            mouse: true,
+            focus_reporting: true,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Probes this process's environment.
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("TERM").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    /// What a terminal with these `TERM` and `COLORTERM` values can do.
+    pub fn from_env(term: Option<&str>, colorterm: Option<&str>) -> Self {
+        // Hardware terminals and their emulations, or no terminal at all.
+        let basic =
+            term.is_none_or(|term| term.is_empty() || term == "dumb" || term.starts_with("vt"));
+        Self {
+            truecolor: matches!(colorterm, Some("truecolor" | "24bit")),
+            mouse: !basic,
+            // The Linux console ignores the mode but doesn't report focus.
+            focus_reporting: !basic && term != Some("linux"),
+        }
+    }
+}
@@ -1,29 +1,1128 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU8, AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use anyhow::Result;
-use ractor::{Actor, ActorRef, cast};
+use ractor::{Actor, ActorRef, ActorStatus, RpcReplyPort, SupervisionEvent, call, cast};
+use ratatui::style::{Style, Stylize};
 use tokio::{
-    sync::oneshot::{self, Sender},
+    sync::oneshot::{self, Receiver, Sender, error::TryRecvError},
     task::{JoinHandle, spawn_blocking},
 };
+use tracing::Instrument;
 
-use crate::AppMessage;
+use crate::{
+    AppMessage, MessageCounts,
+    names::{APP_ACTOR_NAME, COUNTER_ACTOR_NAME, namespaced},
+};
 
+/// Runs each task's simulated work on Tokio's blocking pool, tying up a
+/// pool thread for the task's whole duration. This is the right choice for
+/// CPU-bound work, which would otherwise stall the async workers.
 pub struct Counter;
 
+/// A [`Counter`] whose simulated work is a plain async sleep on the runtime's
+/// workers, so a task costs no thread of its own. This is the right choice
+/// for I/O-bound work; CPU-bound work here would block the workers.
+///
+/// It takes the same [`CounterArgs`] and messages as [`Counter`] and
+/// produces the same values for the same inputs.
+pub struct AsyncCounter;
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
 pub enum CounterMessage {
+    /// Shorthand for an [`CounterMessage::IncrementWithMetadata`] from
+    /// `from`, with an `"unnamed"` label from an `"unknown"` source.
+    Increment { from: u8 },
+    /// The value is where the increment starts, not the counter to
+    /// increment.
+    #[deprecated(note = "renamed to `CounterMessage::Increment`")]
     IncrementCounter(u8),
+    /// Increments from `cur`; `label` and `source` are carried through to the
+    /// app's audit trail.
+    IncrementWithMetadata {
+        cur: u8,
+        label: String,
+        source: String,
+    },
+    /// Applies the transform `times` times over from `cur`, as one task.
+    IncrementBy {
+        cur: u8,
+        times: u8,
+        label: String,
+        source: String,
+    },
+    /// Decrements from `from`, after the same simulated work as an
+    /// increment.
+    Decrement { from: u8 },
+    #[deprecated(note = "renamed to `CounterMessage::Decrement`")]
+    DecrementCounter(u8),
+    /// Cancels any running task and sets the value to zero immediately.
+    Reset,
+    /// Cancels any running task and sets the value immediately.
+    SetValue(u8),
+    /// Replies with the running task's percentage complete, or `None` when
+    /// no task is running.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    GetProgress(RpcReplyPort<Option<u8>>),
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    GetStats(RpcReplyPort<CounterStats>),
+    /// Replies with what the counter is doing. A supervisor answers this
+    /// itself, so it's answered even while the counter is busy.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    Status(RpcReplyPort<CounterStatus>),
+    /// Changes how long the simulated work takes. A task already running
+    /// keeps its original duration.
+    SetWorkDuration(Duration),
+    /// Sent by the app once it's able to receive updates, which go to the
+    /// given ref from then on. Work requested before this is held until it
+    /// arrives.
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_actor"
+        )
+    )]
+    AppReady(ActorRef<AppMessage>),
+}
+
+impl std::fmt::Display for CounterMessage {
+    #[allow(deprecated)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CounterMessage::Increment { from } => write!(f, "CounterMessage::Increment({})", from),
+            CounterMessage::IncrementCounter(cur) => {
+                write!(f, "CounterMessage::IncrementCounter({})", cur)
+            }
+            CounterMessage::IncrementWithMetadata { cur, label, source } => write!(
+                f,
+                "CounterMessage::IncrementWithMetadata({}, {:?}, {:?})",
+                cur, label, source
+            ),
+            CounterMessage::IncrementBy {
+                cur,
+                times,
+                label,
+                source,
+            } => write!(
+                f,
+                "CounterMessage::IncrementBy({}, {}, {:?}, {:?})",
+                cur, times, label, source
+            ),
+            CounterMessage::Decrement { from } => write!(f, "CounterMessage::Decrement({})", from),
+            CounterMessage::DecrementCounter(cur) => {
+                write!(f, "CounterMessage::DecrementCounter({})", cur)
+            }
+            CounterMessage::Reset => write!(f, "CounterMessage::Reset"),
+            CounterMessage::SetValue(val) => write!(f, "CounterMessage::SetValue({})", val),
+            CounterMessage::GetProgress(_) => write!(f, "CounterMessage::GetProgress"),
+            CounterMessage::GetStats(_) => write!(f, "CounterMessage::GetStats"),
+            CounterMessage::Status(_) => write!(f, "CounterMessage::Status"),
+            CounterMessage::SetWorkDuration(duration) => {
+                write!(f, "CounterMessage::SetWorkDuration({:?})", duration)
+            }
+            CounterMessage::AppReady(app) => {
+                write!(f, "CounterMessage::AppReady({})", app.get_id())
+            }
+        }
+    }
+}
+
+impl CounterMessage {
+    /// The variant's name, for tracing and message counts.
+    #[allow(deprecated)]
+    fn message_type(&self) -> &'static str {
+        match self {
+            CounterMessage::Increment { .. } => "Increment",
+            CounterMessage::IncrementCounter(_) => "IncrementCounter",
+            CounterMessage::IncrementWithMetadata { .. } => "IncrementWithMetadata",
+            CounterMessage::IncrementBy { .. } => "IncrementBy",
+            CounterMessage::Decrement { .. } => "Decrement",
+            CounterMessage::DecrementCounter(_) => "DecrementCounter",
+            CounterMessage::Reset => "Reset",
+            CounterMessage::SetValue(_) => "SetValue",
+            CounterMessage::GetProgress(_) => "GetProgress",
+            CounterMessage::GetStats(_) => "GetStats",
+            CounterMessage::Status(_) => "Status",
+            CounterMessage::SetWorkDuration(_) => "SetWorkDuration",
+            CounterMessage::AppReady(_) => "AppReady",
+        }
+    }
+}
+
+/// Identifies a counter task in logs, spans, and stats. Unique within the
+/// process, across counters and restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaskId(pub u64);
+
+impl TaskId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        TaskId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl std::fmt::Display for TaskId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "task-{}", self.0)
+    }
+}
+
+/// Task and message counts since the counter started.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct CounterStats {
+    pub started: u64,
+    pub completed: u64,
+    pub cancelled: u64,
+    /// Tasks the runtime wouldn't run, as when it's shutting down.
+    pub failed_starts: u64,
+    /// The value most recently sent to the app, by a task or a reset or
+    /// set.
+    pub last_completed: Option<u8>,
+    /// The most recently started task, which may since have finished.
+    pub latest_task: Option<TaskId>,
+    /// Messages the counter itself has handled; those a supervisor answers
+    /// aren't counted.
+    pub messages: MessageCounts,
+}
+
+/// What a counter is doing, for dashboards polling [`CounterMessage::Status`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum CounterStatus {
+    #[default]
+    Idle,
+    /// A task is working; `progress` is its percentage complete.
+    Running { progress: u8 },
+    /// A task has been told to stop and hasn't yet.
+    Cancelling,
+}
+
+/// Typed access to a running [`Counter`] (or the [`CounterSupervisor`] in
+/// front of one), hiding its message protocol.
+#[derive(Debug, Clone)]
+pub struct CounterHandle {
+    actor: ActorRef<CounterMessage>,
+}
+
+impl CounterHandle {
+    /// What the counter registers as.
+    pub const NAME: &str = COUNTER_ACTOR_NAME;
+
+    pub fn new(actor_ref: ActorRef<CounterMessage>) -> Self {
+        Self { actor: actor_ref }
+    }
+
+    /// Whether the counter is up, or on its way up, to take messages.
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self.actor.get_status(),
+            ActorStatus::Unstarted | ActorStatus::Starting | ActorStatus::Running
+        )
+    }
+
+    pub async fn increment(&self, from: u8) -> Result<()> {
+        Ok(cast!(self.actor, CounterMessage::Increment { from })?)
+    }
+
+    pub async fn increment_with_metadata(
+        &self,
+        from: u8,
+        label: String,
+        source: String,
+    ) -> Result<()> {
+        Ok(cast!(
+            self.actor,
+            CounterMessage::IncrementWithMetadata {
+                cur: from,
+                label,
+                source,
+            }
+        )?)
+    }
+
+    pub async fn increment_by(
+        &self,
+        from: u8,
+        times: u8,
+        label: String,
+        source: String,
+    ) -> Result<()> {
+        Ok(cast!(
+            self.actor,
+            CounterMessage::IncrementBy {
+                cur: from,
+                times,
+                label,
+                source,
+            }
+        )?)
+    }
+
+    pub async fn decrement(&self, from: u8) -> Result<()> {
+        Ok(cast!(self.actor, CounterMessage::Decrement { from })?)
+    }
+
+    pub async fn reset(&self) -> Result<()> {
+        Ok(cast!(self.actor, CounterMessage::Reset)?)
+    }
+
+    pub async fn set_value(&self, val: u8) -> Result<()> {
+        Ok(cast!(self.actor, CounterMessage::SetValue(val))?)
+    }
+
+    pub async fn get_progress(&self) -> Result<Option<u8>> {
+        Ok(call!(self.actor, CounterMessage::GetProgress)?)
+    }
+
+    pub async fn get_stats(&self) -> Result<CounterStats> {
+        Ok(call!(self.actor, CounterMessage::GetStats)?)
+    }
+
+    pub async fn status(&self) -> Result<CounterStatus> {
+        Ok(call!(self.actor, CounterMessage::Status)?)
+    }
+
+    pub async fn set_work_duration(&self, duration: Duration) -> Result<()> {
+        Ok(cast!(
+            self.actor,
+            CounterMessage::SetWorkDuration(duration)
+        )?)
+    }
+
+    pub async fn app_ready(&self, app: ActorRef<AppMessage>) -> Result<()> {
+        Ok(cast!(self.actor, CounterMessage::AppReady(app))?)
+    }
+}
+
+/// Why a counter task stopped short of delivering its result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockTaskErrorKind {
+    /// The counter cancelled the task, or went away without doing so.
+    Cancelled,
+    /// Sending to the actor failed for the given reason.
+    CastFailed(String),
+}
+
+impl std::fmt::Display for BlockTaskErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockTaskErrorKind::Cancelled => write!(f, "cancelled"),
+            BlockTaskErrorKind::CastFailed(reason) => write!(f, "cast failed: {}", reason),
+        }
+    }
+}
+
+/// A counter task's failure, with the actor involved and what the task was
+/// doing at the time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTaskError {
+    pub kind: BlockTaskErrorKind,
+    pub actor: String,
+    pub context: String,
+}
+
+impl std::fmt::Display for BlockTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.context, self.actor, self.kind)
+    }
+}
+
+impl std::error::Error for BlockTaskError {}
+
+impl From<ractor::MessagingErr<AppMessage>> for BlockTaskError {
+    fn from(err: ractor::MessagingErr<AppMessage>) -> Self {
+        let context = match &err {
+            ractor::MessagingErr::SendErr(message) => format!("sending {}", message),
+            _ => "sending to the app".to_string(),
+        };
+        Self {
+            kind: BlockTaskErrorKind::CastFailed(err.to_string()),
+            actor: APP_ACTOR_NAME.to_string(),
+            context,
+        }
+    }
 }
 
 #[derive(Debug)]
 struct BlockTask {
+    id: TaskId,
     canceller: Sender<()>,
-    handle: JoinHandle<Result<()>>,
+    handle: JoinHandle<Result<(), BlockTaskError>>,
+    /// Percentage complete, written by the task.
+    progress: Arc<AtomicU8>,
+}
+
+/// How long an increment's simulated work takes unless configured otherwise.
+pub const DEFAULT_WORK_DURATION: Duration = Duration::from_secs(10);
+
+/// How often a task checks for cancellation unless configured otherwise.
+pub const DEFAULT_CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Queue depth past which a counter warns unless configured otherwise.
+pub const DEFAULT_DEPTH_WARNING: usize = 10;
+
+/// How long a stopping counter waits for a cancelled task before giving up
+/// on it.
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many times a task reports progress.
+const WORK_STEPS: u32 = 10;
+
+/// A pure function computing the counter's next value from its current one.
+pub type Transform = dyn Fn(u8) -> u8 + Send + Sync + 'static;
+
+#[derive(Clone)]
+pub struct CounterArgs {
+    /// Applied to the current value once an increment's work completes.
+    pub transform: Arc<Transform>,
+    /// Survives restarts so a restarted counter can resume the increment
+    /// that was in flight when it crashed.
+    pub checkpoint: Checkpoint,
+    /// How long each task's simulated work takes.
+    pub work_duration: Duration,
+    /// Set when this is one of several counters shown by the app; its values
+    /// are then sent as [`AppMessage::UpdateNamedCount`] and its progress
+    /// isn't reported.
+    pub name: Option<String>,
+    /// Messages waiting for the counter, counted by the supervisor as it
+    /// forwards them.
+    pub mailbox: MailboxDepth,
+    /// Queue depth past which the counter warns that it's falling behind.
+    pub depth_warning: usize,
+    /// How often a task checks whether it's been cancelled, which bounds how
+    /// long cancelling takes. Very short intervals wake the task constantly,
+    /// costing CPU in context switches; zero checks once per progress step.
+    pub cancel_check_interval: Duration,
+    /// Kept up to date by the counter and its tasks; shared with the
+    /// supervisor so it can answer [`CounterMessage::Status`].
+    pub status: SharedStatus,
+    /// Where updates go. When the app is spawned after the counter, leave
+    /// this unset and have the app send [`CounterMessage::AppReady`].
+    pub app: Option<ActorRef<AppMessage>>,
+    /// The [`CounterSupervisor`] registers its counter under this
+    /// namespace, as [`crate::names::namespaced`] does.
+    pub namespace: Option<String>,
+}
+
+impl Default for CounterArgs {
+    fn default() -> Self {
+        Self {
+            transform: Arc::new(|x: u8| x.saturating_add(1)),
+            checkpoint: Checkpoint::default(),
+            work_duration: DEFAULT_WORK_DURATION,
+            name: None,
+            mailbox: MailboxDepth::default(),
+            depth_warning: DEFAULT_DEPTH_WARNING,
+            cancel_check_interval: DEFAULT_CANCEL_CHECK_INTERVAL,
+            status: SharedStatus::default(),
+            app: None,
+            namespace: None,
+        }
+    }
+}
+
+/// Where a counter runs its tasks' simulated work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkMode {
+    Blocking,
+    Async,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+/// An increment or decrement that was started but hasn't completed.
+#[derive(Debug, Clone)]
+struct PendingIncrement {
+    cur: u8,
+    direction: Direction,
+    times: u8,
+    label: String,
+    source: String,
+}
+
+impl PendingIncrement {
+    fn into_message(self) -> CounterMessage {
+        match self.direction {
+            Direction::Up if self.times == 1 => CounterMessage::IncrementWithMetadata {
+                cur: self.cur,
+                label: self.label,
+                source: self.source,
+            },
+            Direction::Up => CounterMessage::IncrementBy {
+                cur: self.cur,
+                times: self.times,
+                label: self.label,
+                source: self.source,
+            },
+            Direction::Down => CounterMessage::Decrement { from: self.cur },
+        }
+    }
+}
+
+/// Everything a running task needs, moved into it when it's spawned.
+struct Work {
+    id: TaskId,
+    span: tracing::Span,
+    app: ActorRef<AppMessage>,
+    name: Option<String>,
+    cancel: Receiver<()>,
+    tick: Duration,
+    check_interval: Duration,
+    progress: Arc<AtomicU8>,
+    status: SharedStatus,
+    completed: Arc<AtomicU64>,
+    last_completed: Arc<Mutex<Option<u8>>>,
+    checkpoint: Checkpoint,
+    step: Arc<Transform>,
+    cur: u8,
+    label: String,
+    source: String,
+}
+
+impl Work {
+    /// Fails if the task should stop. A closed channel means the counter died
+    /// without cancelling it; stop so a restarted counter can redo the work.
+    fn check_cancelled(&mut self) -> Result<(), BlockTaskError> {
+        if let Ok(()) | Err(TryRecvError::Closed) = self.cancel.try_recv() {
+            tracing::info!(task_id = %self.id, "Got cancellation token");
+            return Err(BlockTaskError {
+                kind: BlockTaskErrorKind::Cancelled,
+                actor: COUNTER_ACTOR_NAME.to_string(),
+                context: format!("{} from {}", self.id, self.cur),
+            });
+        }
+        Ok(())
+    }
+
+    /// One step's wait, cut into pieces no longer than the check interval so
+    /// cancellation is noticed between them.
+    fn slices(&self) -> impl Iterator<Item = Duration> + use<> {
+        let (tick, interval) = (self.tick, self.check_interval);
+        let mut waited = Duration::ZERO;
+        std::iter::from_fn(move || {
+            let slice = if interval.is_zero() {
+                tick - waited
+            } else {
+                (tick - waited).min(interval)
+            };
+            waited += slice;
+            (!slice.is_zero()).then_some(slice)
+        })
+    }
+
+    /// The percentages reported along the way, ending at 100. With no work
+    /// to wait on, that's the only one and the task finishes at once.
+    fn steps(&self) -> impl Iterator<Item = u8> + use<> {
+        let steps = if self.tick.is_zero() { 1 } else { WORK_STEPS };
+        (1..=steps).map(move |step| (step * 100 / steps) as u8)
+    }
+
+    fn report(&self, pct: u8) -> Result<(), BlockTaskError> {
+        self.progress.store(pct, Ordering::Relaxed);
+        self.status.set(CounterStatus::Running { progress: pct });
+        if self.name.is_some() {
+            return Ok(());
+        }
+        Ok(self.app.cast(AppMessage::UpdateProgress(pct))?)
+    }
+
+    fn finish(mut self) -> Result<(), BlockTaskError> {
+        tracing::info!(task_id = %self.id, "Finished waiting");
+        let value = (self.step)(self.cur);
+        self.span.record("counter.result", value);
+        // As at 255. The app has the 100% report, which is all it needs to
+        // stop showing the task.
+        if value == self.cur {
+            tracing::info!(task_id = %self.id, "Value unchanged at {}; not sending", value);
+        } else {
+            self.app.cast(count_update(
+                self.name.as_deref(),
+                value,
+                std::mem::take(&mut self.label),
+                std::mem::take(&mut self.source),
+            ))?;
+            *self.last_completed.lock().unwrap() = Some(value);
+        }
+        self.checkpoint.clear();
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl Drop for Work {
+    // However the task ends, it's no longer running.
+    fn drop(&mut self) {
+        self.status.set(CounterStatus::Idle);
+    }
+}
+
+/// The message delivering a new value to the app, addressed by name for one
+/// of several counters.
+fn count_update(name: Option<&str>, value: u8, label: String, source: String) -> AppMessage {
+    match name {
+        Some(name) => AppMessage::UpdateNamedCount(name.to_string(), value),
+        None => AppMessage::UpdateCountWithMetadata {
+            value,
+            label,
+            source,
+        },
+    }
+}
+
+/// Logs how a task ended. Only a panicked task is an error for the counter;
+/// the rest are expected when a task is cancelled or the app is going away.
+fn task_finished(
+    result: Result<Result<(), BlockTaskError>, tokio::task::JoinError>,
+) -> Result<(), ractor::ActorProcessingErr> {
+    match result? {
+        Ok(()) => {}
+        Err(e) => match e.kind {
+            BlockTaskErrorKind::Cancelled => tracing::warn!("Task stopped: {}", e),
+            BlockTaskErrorKind::CastFailed(_) => tracing::error!("Task failed: {}", e),
+        },
+    }
+    Ok(())
+}
+
+/// How many messages are queued for a counter. ractor doesn't expose mailbox
+/// lengths, so the [`CounterSupervisor`] counts each message it forwards and
+/// the counter counts it off again when handling it. A counter spawned
+/// without a supervisor always reads zero.
+#[derive(Debug, Clone, Default)]
+pub struct MailboxDepth(Arc<AtomicUsize>);
+
+impl MailboxDepth {
+    /// Counts a message on its way, returning the depth including it.
+    fn sent(&self) -> usize {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Counts off a message being handled, returning the depth including it.
+    fn received(&self) -> usize {
+        // Messages that didn't come through the supervisor were never
+        // counted, so don't go below zero for them.
+        self.0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| {
+                Some(depth.saturating_sub(1))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Forgets messages lost with a counter that died.
+    fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
 }
 
-#[derive(Default, Debug)]
+/// The counter's [`CounterStatus`], shared between a counter, its tasks, and
+/// its supervisor.
+#[derive(Debug, Clone, Default)]
+pub struct SharedStatus(Arc<Mutex<CounterStatus>>);
+
+impl SharedStatus {
+    fn set(&self, status: CounterStatus) {
+        *self.0.lock().unwrap() = status;
+    }
+
+    fn get(&self) -> CounterStatus {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// The counter's outstanding work, shared between a counter and whatever
+/// restarts it.
+#[derive(Debug, Clone, Default)]
+pub struct Checkpoint(Arc<Mutex<Option<PendingIncrement>>>);
+
+impl Checkpoint {
+    fn save(&self, pending: PendingIncrement) {
+        *self.0.lock().unwrap() = Some(pending);
+    }
+
+    fn clear(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    fn pending(&self) -> Option<PendingIncrement> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
 pub struct CounterState {
+    #[cfg_attr(feature = "recording", serde(skip))]
+    mode: WorkMode,
+    /// The running task, if any; none until the first increment or
+    /// decrement.
+    #[cfg_attr(feature = "recording", serde(skip))]
     prev: Option<BlockTask>,
+    // Shared with each task, hence the `Arc`.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    transform: Arc<Transform>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    checkpoint: Checkpoint,
+    work_duration: Duration,
+    cancel_check_interval: Duration,
+    name: Option<String>,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    mailbox: MailboxDepth,
+    #[cfg_attr(feature = "recording", serde(skip))]
+    status: SharedStatus,
+    depth_warning: usize,
+    /// The deepest the queue has been since this start, from zero.
+    max_observed_depth: usize,
+    /// Stats count from this start, not across restarts.
+    started: u64,
+    cancelled: u64,
+    failed_starts: u64,
+    latest_task: Option<TaskId>,
+    messages: MessageCounts,
+    // Bumped by the tasks as they finish.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    completed: Arc<AtomicU64>,
+    // Set by the tasks too, as well as by resets and sets.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    last_completed: Arc<Mutex<Option<u8>>>,
+    // Unset until the app is ready, unless given up front.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    app: Option<ActorRef<AppMessage>>,
+    // The latest task requested before the app was ready.
+    #[cfg_attr(feature = "recording", serde(skip))]
+    deferred: Option<(PendingIncrement, Arc<Transform>)>,
+}
+
+impl std::fmt::Debug for CounterState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CounterState")
+            .field("mode", &self.mode)
+            .field("prev", &self.prev)
+            .field("checkpoint", &self.checkpoint)
+            .field("work_duration", &self.work_duration)
+            .field("stats", &self.stats())
+            .field("status", &self.status.get())
+            .field("app_ready", &self.app.is_some())
+            .field("max_observed_depth", &self.max_observed_depth)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A blocking counter's state with [`CounterArgs::default`], as a
+/// [`Counter`] starts with when given them.
+impl Default for CounterState {
+    fn default() -> Self {
+        Self::with_args(CounterArgs::default(), WorkMode::Blocking)
+    }
+}
+
+impl CounterState {
+    /// Everything not taken from `args` starts empty, as the fields say.
+    #[tracing::instrument(name = "counter.start", skip(args))]
+    fn with_args(args: CounterArgs, mode: WorkMode) -> Self {
+        Self {
+            mode,
+            prev: None,
+            transform: args.transform,
+            checkpoint: args.checkpoint,
+            work_duration: args.work_duration,
+            cancel_check_interval: args.cancel_check_interval,
+            name: args.name,
+            mailbox: args.mailbox,
+            status: args.status,
+            depth_warning: args.depth_warning,
+            max_observed_depth: 0,
+            started: 0,
+            cancelled: 0,
+            failed_starts: 0,
+            latest_task: None,
+            messages: MessageCounts::default(),
+            completed: Arc::new(AtomicU64::new(0)),
+            last_completed: Arc::new(Mutex::new(None)),
+            app: args.app,
+            deferred: None,
+        }
+    }
+
+    fn stats(&self) -> CounterStats {
+        CounterStats {
+            started: self.started,
+            completed: self.completed.load(Ordering::Relaxed),
+            last_completed: *self.last_completed.lock().unwrap(),
+            cancelled: self.cancelled,
+            failed_starts: self.failed_starts,
+            latest_task: self.latest_task,
+            messages: self.messages.clone(),
+        }
+    }
+
+    /// Cancels the running task, if any, and waits for it to wind down.
+    async fn cancel_prev(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        if let Some(task) = self.prev.take() {
+            let id = task.id;
+            tracing::info!(task_id = %id, "Handling previous task");
+            let handle = self.cancel(task);
+            tracing::info!(task_id = %id, "Awaiting task");
+            task_finished(handle.await)?;
+        }
+        Ok(())
+    }
+
+    /// Tells `task` to stop unless it already has, handing back its handle
+    /// for the caller to wait on however suits it.
+    #[must_use = "the task may still be winding down"]
+    fn cancel(&mut self, task: BlockTask) -> JoinHandle<Result<(), BlockTaskError>> {
+        let BlockTask {
+            id,
+            canceller,
+            handle,
+            ..
+        } = task;
+        if !handle.is_finished() {
+            tracing::info!(task_id = %id, "Not yet finished; cancelling");
+            self.status.set(CounterStatus::Cancelling);
+            // The task may finish and drop its receiver between the check
+            // and the send, leaving nothing to cancel.
+            if canceller.send(()).is_ok() {
+                self.cancelled += 1;
+            }
+        }
+        handle
+    }
+
+    /// Replaces any running task with one that applies `step` to `pending.cur`
+    /// once the simulated work completes.
+    async fn start_task(
+        &mut self,
+        pending: PendingIncrement,
+        step: Arc<Transform>,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        let Some(app) = self.app.clone() else {
+            // Nowhere to send the result yet; hold the task until the app is
+            // up. Like a running task, a newer request replaces it.
+            tracing::info!("App not ready; deferring task from {}", pending.cur);
+            self.checkpoint.save(pending.clone());
+            self.deferred = Some((pending, step));
+            return Ok(());
+        };
+        self.cancel_prev().await?;
+        let id = TaskId::next();
+        tracing::info!(
+            task_id = %id,
+            "Starting {:?} task from {}",
+            pending.direction,
+            pending.cur
+        );
+
+        let (send, recv) = oneshot::channel::<()>();
+        let progress = Arc::new(AtomicU8::new(0));
+        self.checkpoint.save(pending.clone());
+        let span = tracing::info_span!(
+            "counter.task",
+            counter.current = pending.cur,
+            counter.step = pending.times,
+            counter.direction = ?pending.direction,
+            counter.result = tracing::field::Empty,
+            task.id = %id,
+            task.attempt = self.started + 1,
+        );
+        let mut work = Work {
+            id,
+            span: span.clone(),
+            app,
+            name: self.name.clone(),
+            cancel: recv,
+            tick: self.work_duration / WORK_STEPS,
+            check_interval: self.cancel_check_interval,
+            progress: progress.clone(),
+            status: self.status.clone(),
+            completed: self.completed.clone(),
+            last_completed: self.last_completed.clone(),
+            checkpoint: self.checkpoint.clone(),
+            step,
+            cur: pending.cur,
+            label: pending.label,
+            source: pending.source,
+        };
+
+        // Before spawning, so a task that finishes at once leaves it idle.
+        self.status.set(CounterStatus::Running { progress: 0 });
+        let prev: JoinHandle<Result<(), BlockTaskError>> = match self.mode {
+            WorkMode::Blocking => spawn_blocking(move || {
+                let _entered = span.enter();
+                // Simulate CPU-bound work
+                for pct in work.steps() {
+                    for slice in work.slices() {
+                        std::thread::sleep(slice);
+                        work.check_cancelled()?;
+                    }
+                    work.report(pct)?;
+                }
+                work.finish()
+            }),
+            WorkMode::Async => tokio::spawn(
+                async move {
+                    // Simulate I/O-bound work
+                    for pct in work.steps() {
+                        for slice in work.slices() {
+                            tokio::time::sleep(slice).await;
+                            work.check_cancelled()?;
+                        }
+                        work.report(pct)?;
+                    }
+                    work.finish()
+                }
+                .instrument(span),
+            ),
+        };
+
+        self.started += 1;
+        self.latest_task = Some(id);
+        if let Some(handle) = self.check_started(prev).await? {
+            self.prev = Some(BlockTask {
+                id,
+                canceller: send,
+                handle,
+                progress,
+            });
+        }
+        Ok(())
+    }
+
+    /// Hands back `handle` unless its task has already finished. Spawning
+    /// can't fail outright, but while the runtime shuts down it returns a
+    /// handle to a task that never ran, which would otherwise fail the next
+    /// [`CounterState::cancel_prev`].
+    async fn check_started(
+        &mut self,
+        handle: JoinHandle<Result<(), BlockTaskError>>,
+    ) -> Result<Option<JoinHandle<Result<(), BlockTaskError>>>, ractor::ActorProcessingErr> {
+        tokio::task::yield_now().await;
+        if !handle.is_finished() {
+            return Ok(Some(handle));
+        }
+        match handle.await {
+            Err(e) => {
+                tracing::error!("Task failed to start: {}", e);
+                self.failed_starts += 1;
+                self.status.set(CounterStatus::Idle);
+                if let Some(app) = &self.app
+                    && let Err(e) = app.cast(AppMessage::Notify(
+                        "Counter task failed to start".to_string(),
+                        Style::new().red(),
+                    ))
+                {
+                    tracing::warn!("Dropped notification: {}", e);
+                }
+            }
+            result => task_finished(result)?,
+        }
+        Ok(None)
+    }
+
+    /// Cancels any running task and sets the value right away.
+    async fn set_now(&mut self, value: u8, label: &str) -> Result<(), ractor::ActorProcessingErr> {
+        self.cancel_prev().await?;
+        self.deferred = None;
+        self.checkpoint.clear();
+        let update = count_update(
+            self.name.as_deref(),
+            value,
+            label.to_string(),
+            COUNTER_ACTOR_NAME.to_string(),
+        );
+        match &self.app {
+            Some(app) => match app.cast(update) {
+                Ok(()) => *self.last_completed.lock().unwrap() = Some(value),
+                Err(e) => tracing::warn!("Dropped update: {}", e),
+            },
+            None => tracing::warn!("Dropped update {}: app not ready", update),
+        }
+        Ok(())
+    }
+
+    /// Resumes the work that was in flight when a previous counter died.
+    fn resume(&self, myself: &ActorRef<CounterMessage>) -> Result<(), ractor::ActorProcessingErr> {
+        if let Some(pending) = self.checkpoint.pending() {
+            tracing::info!("Resuming task from {} after restart", pending.cur);
+            cast!(myself, pending.into_message())?;
+        }
+        Ok(())
+    }
+
+    /// Shared by [`Counter`] and [`AsyncCounter`], which differ only in how
+    /// [`CounterState::start_task`] runs the work.
+    #[tracing::instrument(name = "counter.handle", skip_all, fields(counter.message = %message))]
+    async fn handle(&mut self, message: CounterMessage) -> Result<(), ractor::ActorProcessingErr> {
+        let depth = self.mailbox.received();
+        report_depth(
+            self.app.as_ref(),
+            self.name.as_deref(),
+            depth.saturating_sub(1),
+        );
+        self.max_observed_depth = self.max_observed_depth.max(depth);
+        tracing::info!("Queue depth {}", depth);
+        if depth > self.depth_warning {
+            tracing::warn!(
+                "Counter is falling behind: {} messages queued (most seen {})",
+                depth,
+                self.max_observed_depth
+            );
+        }
+        self.messages.record(message.message_type());
+        match message {
+            #[allow(deprecated)]
+            CounterMessage::Increment { from: cur } | CounterMessage::IncrementCounter(cur) => {
+                let pending = PendingIncrement {
+                    cur,
+                    direction: Direction::Up,
+                    times: 1,
+                    label: "unnamed".to_string(),
+                    source: "unknown".to_string(),
+                };
+                self.start_task(pending, self.transform.clone()).await?;
+            }
+            CounterMessage::IncrementWithMetadata { cur, label, source } => {
+                let pending = PendingIncrement {
+                    cur,
+                    direction: Direction::Up,
+                    times: 1,
+                    label,
+                    source,
+                };
+                self.start_task(pending, self.transform.clone()).await?;
+            }
+            CounterMessage::IncrementBy {
+                cur,
+                times,
+                label,
+                source,
+            } => {
+                let pending = PendingIncrement {
+                    cur,
+                    direction: Direction::Up,
+                    times,
+                    label,
+                    source,
+                };
+                let transform = self.transform.clone();
+                let step = Arc::new(move |x| (0..times).fold(x, |x, _| transform(x)));
+                self.start_task(pending, step).await?;
+            }
+            #[allow(deprecated)]
+            CounterMessage::Decrement { from: cur } | CounterMessage::DecrementCounter(cur) => {
+                let pending = PendingIncrement {
+                    cur,
+                    direction: Direction::Down,
+                    times: 1,
+                    label: "decrement".to_string(),
+                    source: "unknown".to_string(),
+                };
+                self.start_task(pending, Arc::new(|x: u8| x.saturating_sub(1)))
+                    .await?;
+            }
+            CounterMessage::Reset => self.set_now(0, "reset").await?,
+            CounterMessage::SetValue(val) => self.set_now(val, "set").await?,
+            CounterMessage::GetProgress(reply) => {
+                let progress = self
+                    .prev
+                    .as_ref()
+                    .filter(|task| !task.handle.is_finished())
+                    .map(|task| task.progress.load(Ordering::Relaxed));
+                reply.send(progress)?;
+            }
+            CounterMessage::GetStats(reply) => {
+                reply.send(self.stats())?;
+            }
+            CounterMessage::Status(reply) => {
+                reply.send(self.status.get())?;
+            }
+            CounterMessage::SetWorkDuration(duration) => {
+                tracing::info!("Setting work duration to {:?}", duration);
+                self.work_duration = duration;
+            }
+            CounterMessage::AppReady(app) => {
+                tracing::info!("App is ready at {}", app.get_id());
+                self.app = Some(app);
+                if let Some((pending, step)) = self.deferred.take() {
+                    self.start_task(pending, step).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "counter.stop", skip_all)]
+    async fn stop(&mut self) -> Result<(), ractor::ActorProcessingErr> {
+        if let Some(task) = self.prev.take() {
+            let mut handle = self.cancel(task);
+            // Don't leave the task running if we fail partway through.
+            let _guard = AbortOnDrop(handle.abort_handle());
+            tokio::select! {
+                result = &mut handle => task_finished(result)?,
+                _ = tokio::time::sleep(STOP_TIMEOUT) => {
+                    // Aborting can't stop blocking work that's under way; it
+                    // winds down once it next checks for cancellation.
+                    tracing::warn!("Task didn't stop within {:?}; aborting", STOP_TIMEOUT);
+                    handle.abort();
+                }
+            }
+        }
+        // A clean stop abandons any outstanding work.
+        self.checkpoint.clear();
+
+        Ok(())
+    }
+}
+
+/// Aborts a task when dropped, which does nothing once it has finished.
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Tells the app how many messages are waiting for the main counter. Named
+/// counters' queues aren't shown, and there's no one to tell before the app
+/// is ready.
+fn report_depth(app: Option<&ActorRef<AppMessage>>, name: Option<&str>, waiting: usize) {
+    let Some(app) = app.filter(|_| name.is_none()) else {
+        return;
+    };
+    if let Err(e) = app.cast(AppMessage::QueueDepth(waiting)) {
+        tracing::warn!("Dropped queue depth: {}", e);
+    }
 }
 
 impl Actor for Counter {
@@ -31,14 +1130,22 @@ impl Actor for Counter {
 
     type State = CounterState;
 
-    type Arguments = ();
+    type Arguments = CounterArgs;
 
     async fn pre_start(
         &self,
         _myself: ractor::ActorRef<Self::Msg>,
-        _args: Self::Arguments,
+        args: Self::Arguments,
     ) -> Result<Self::State, ractor::ActorProcessingErr> {
-        Ok(CounterState::default())
+        Ok(CounterState::with_args(args, WorkMode::Blocking))
+    }
+
+    async fn post_start(
+        &self,
+        myself: ractor::ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        state.resume(&myself)
     }
 
     async fn handle(
@@ -47,45 +1154,48 @@ impl Actor for Counter {
         message: Self::Msg,
         state: &mut Self::State,
     ) -> std::result::Result<(), ractor::ActorProcessingErr> {
-        if let Some(BlockTask { canceller, handle }) = state.prev.take() {
-            tracing::info!("Handling previous task");
-            if !handle.is_finished() {
-                tracing::info!("Not yet finished; cancelling");
-                canceller.send(()).unwrap();
-            }
-            tracing::info!("Awaiting task");
-            handle.await??;
-        }
-        tracing::info!("Incrementing counter");
-        let CounterMessage::IncrementCounter(cur) = message;
-
-        let app: ActorRef<AppMessage> = ractor::registry::where_is("app".to_string())
-            .expect("App??")
-            .into();
-
-        let (send, mut recv) = oneshot::channel::<()>();
-
-        let prev: JoinHandle<Result<()>> = spawn_blocking(move || {
-            // Simulate CPU-bound work
-            for _ in 0..10 {
-                std::thread::sleep(Duration::from_secs(1));
-                if let Ok(()) = recv.try_recv() {
-                    tracing::info!("Got cancellation token");
-                    return Ok(());
-                }
-            }
-            tracing::info!("Finished waiting");
-            cast!(app, AppMessage::UpdateCount(cur + 1))?;
+        state.handle(message).await
+    }
 
-            Ok(())
-        });
+    async fn post_stop(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> std::result::Result<(), ractor::ActorProcessingErr> {
+        state.stop().await
+    }
+}
 
-        state.prev = Some(BlockTask {
-            canceller: send,
-            handle: prev,
-        });
+impl Actor for AsyncCounter {
+    type Msg = CounterMessage;
 
-        Ok(())
+    type State = CounterState;
+
+    type Arguments = CounterArgs;
+
+    async fn pre_start(
+        &self,
+        _myself: ractor::ActorRef<Self::Msg>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        Ok(CounterState::with_args(args, WorkMode::Async))
+    }
+
+    async fn post_start(
+        &self,
+        myself: ractor::ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        state.resume(&myself)
+    }
+
+    async fn handle(
+        &self,
+        _myself: ractor::ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> std::result::Result<(), ractor::ActorProcessingErr> {
+        state.handle(message).await
     }
 
     async fn post_stop(
@@ -93,13 +1203,1120 @@ impl Actor for Counter {
         _myself: ActorRef<Self::Msg>,
         state: &mut Self::State,
     ) -> std::result::Result<(), ractor::ActorProcessingErr> {
-        if let Some(BlockTask { canceller, handle }) = state.prev.take() {
-            if !handle.is_finished() {
-                canceller.send(()).unwrap();
+        state.stop().await
+    }
+}
+
+/// Runs a [`Counter`] as a child, forwarding messages to it, and restarts it
+/// from its checkpoint if it fails. Callers holding the supervisor's ref
+/// never see the restart.
+pub struct CounterSupervisor;
+
+pub struct CounterSupervisorState {
+    // A restarted counter starts from these, including the app once it's
+    // ready, so it doesn't wait for the app again.
+    args: CounterArgs,
+    counter: ActorRef<CounterMessage>,
+}
+
+impl Actor for CounterSupervisor {
+    type Msg = CounterMessage;
+
+    type State = CounterSupervisorState;
+
+    type Arguments = CounterArgs;
+
+    async fn pre_start(
+        &self,
+        myself: ractor::ActorRef<Self::Msg>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        let (counter, _) = Counter::spawn_linked(
+            Some(namespaced(args.namespace.as_deref(), COUNTER_ACTOR_NAME)),
+            Counter,
+            args.clone(),
+            myself.get_cell(),
+        )
+        .await?;
+        Ok(CounterSupervisorState { args, counter })
+    }
+
+    async fn handle(
+        &self,
+        _myself: ractor::ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match message {
+            CounterMessage::Status(reply) => {
+                reply.send(state.args.status.get())?;
+                return Ok(());
             }
-            handle.await??;
+            CounterMessage::AppReady(ref app) => state.args.app = Some(app.clone()),
+            // Keep restarts on the latest duration.
+            CounterMessage::SetWorkDuration(duration) => state.args.work_duration = duration,
+            _ => {}
         }
+        // Reported first so the counter's report on taking the message off
+        // the queue can't reach the app ahead of this one.
+        let depth = state.args.mailbox.sent();
+        report_depth(state.args.app.as_ref(), state.args.name.as_deref(), depth);
+        cast!(state.counter, message)?;
+        Ok(())
+    }
 
+    async fn handle_supervisor_evt(
+        &self,
+        myself: ractor::ActorRef<Self::Msg>,
+        message: SupervisionEvent,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        if let SupervisionEvent::ActorFailed(_, err) = message {
+            tracing::warn!("Counter failed, restarting: {}", err);
+            state.args.mailbox.reset();
+            let (counter, _) = Counter::spawn_linked(
+                Some(namespaced(
+                    state.args.namespace.as_deref(),
+                    COUNTER_ACTOR_NAME,
+                )),
+                Counter,
+                state.args.clone(),
+                myself.get_cell(),
+            )
+            .await?;
+            state.counter = counter;
+        }
         Ok(())
     }
+
+    async fn post_stop(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        _state: &mut Self::State,
+    ) -> std::result::Result<(), ractor::ActorProcessingErr> {
+        // Let the counter cancel its task before we go.
+        myself.stop_children_and_wait(None, None).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::testing::Logs;
+
+    /// Stands in for the app, noting each message it's sent.
+    struct MockApp;
+
+    impl Actor for MockApp {
+        type Msg = AppMessage;
+        type State = Arc<Mutex<Vec<String>>>;
+        type Arguments = Arc<Mutex<Vec<String>>>;
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            received: Self::Arguments,
+        ) -> Result<Self::State, ractor::ActorProcessingErr> {
+            Ok(received)
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            message: Self::Msg,
+            received: &mut Self::State,
+        ) -> Result<(), ractor::ActorProcessingErr> {
+            received.lock().unwrap().push(message.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_default_counter_starts_empty() {
+        let state = CounterState::default();
+        assert_eq!(state.mode, WorkMode::Blocking);
+        assert!(state.prev.is_none());
+        assert!(state.app.is_none());
+        assert!(state.deferred.is_none());
+        assert_eq!(state.work_duration, DEFAULT_WORK_DURATION);
+        assert_eq!(state.max_observed_depth, 0);
+        assert_eq!(state.stats(), CounterStats::default());
+        assert_eq!(state.status.get(), CounterStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn a_task_that_never_ran_is_reported() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app.clone()),
+                ..CounterArgs::default()
+            },
+            WorkMode::Async,
+        );
+        // What a shutting-down runtime hands back: a task cancelled before it
+        // could run.
+        let handle = tokio::spawn(std::future::pending());
+        handle.abort();
+
+        assert!(state.check_started(handle).await.unwrap().is_none());
+        assert_eq!(state.stats().failed_starts, 1);
+        app.drain_and_wait(None).await.unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            [r#"AppMessage::Notify("Counter task failed to start")"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn finished_tasks_record_the_value_sent() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            AsyncCounter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+        assert_eq!(
+            counter_handle.get_stats().await.unwrap().last_completed,
+            None
+        );
+
+        counter_handle.increment(4).await.unwrap();
+        // Recorded by the task just after it sends the update.
+        let last_completed = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                if let Some(value) = counter_handle.get_stats().await.unwrap().last_completed {
+                    return value;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(last_completed, 5);
+        app.drain_and_wait(None).await.unwrap();
+        assert!(
+            received
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|message| message.starts_with("AppMessage::UpdateCountWithMetadata(5"))
+        );
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn results_go_to_the_app_it_was_given() {
+        // Unnamed, so only the ref the counter is given can reach it.
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(4).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        counter.stop(None);
+        handle.await.unwrap();
+        app.drain_and_wait(None).await.unwrap();
+        let received = received.lock().unwrap();
+        assert!(
+            received.iter().any(|message| message
+                == r#"AppMessage::UpdateCountWithMetadata(5, "unnamed", "unknown")"#),
+            "{:?}",
+            received
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_duration_work_increments_at_once() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::ZERO,
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        let started = Instant::now();
+        counter_handle.increment(0).await.unwrap();
+        let stats = wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(stats.last_completed, Some(1));
+        counter.stop(None);
+        handle.await.unwrap();
+        app.drain_and_wait(None).await.unwrap();
+        let received = received.lock().unwrap();
+        // Its one step is the whole of the work.
+        assert!(
+            received
+                .iter()
+                .any(|message| message == "AppMessage::UpdateProgress(100)")
+        );
+    }
+
+    /// Polls the counter's stats until `done` holds of them.
+    async fn wait_for_stats(
+        counter: &CounterHandle,
+        done: impl Fn(&CounterStats) -> bool,
+    ) -> CounterStats {
+        tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                let stats = counter.get_stats().await.unwrap();
+                if done(&stats) {
+                    return stats;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    /// An increment from `cur` as the counter's handler would start it.
+    fn increment(cur: u8) -> PendingIncrement {
+        PendingIncrement {
+            cur,
+            direction: Direction::Up,
+            times: 1,
+            label: "unnamed".to_string(),
+            source: "unknown".to_string(),
+        }
+    }
+
+    /// A task running `work`, which is handed the cancellation receiver.
+    fn task_running<F>(work: impl FnOnce(Receiver<()>) -> F) -> BlockTask
+    where
+        F: Future<Output = Result<(), BlockTaskError>> + Send + 'static,
+    {
+        let (canceller, cancel) = oneshot::channel();
+        BlockTask {
+            id: TaskId::next(),
+            canceller,
+            handle: tokio::spawn(work(cancel)),
+            progress: Arc::new(AtomicU8::new(0)),
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_finished_task_leaves_it_be() {
+        let task = task_running(|_| async { Ok(()) });
+        while !task.handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        let mut state = CounterState::default();
+
+        assert_eq!(state.cancel(task).await.unwrap(), Ok(()));
+        assert_eq!(state.stats().cancelled, 0);
+        assert_eq!(state.status.get(), CounterStatus::Idle);
+    }
+
+    /// A task that's dropped its canceller, as one finishing does, but won't
+    /// return until `release` is sent.
+    fn finishing_task() -> (BlockTask, oneshot::Sender<()>) {
+        let (release, released) = oneshot::channel::<()>();
+        let task = task_running(|cancel| async move {
+            drop(cancel);
+            let _ = released.await;
+            Ok(())
+        });
+        (task, release)
+    }
+
+    #[tokio::test]
+    async fn cancelling_as_a_task_finishes_leaves_it_be() {
+        let (task, release) = finishing_task();
+        while !task.canceller.is_closed() {
+            tokio::task::yield_now().await;
+        }
+        let mut state = CounterState::default();
+
+        // Not finished, so it tries to cancel, but there's no one to tell.
+        let handle = state.cancel(task);
+        assert_eq!(state.stats().cancelled, 0);
+        release.send(()).unwrap();
+        assert_eq!(handle.await.unwrap(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn incrementing_after_a_task_finished_starts_another() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(0).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        // Long enough for the finished task's receiver to be gone.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        counter_handle.increment(1).await.unwrap();
+        let stats = wait_for_stats(&counter_handle, |stats| stats.completed == 2).await;
+        assert_eq!(stats.last_completed, Some(2));
+        assert_eq!(stats.cancelled, 0);
+        assert_eq!(counter.get_status(), ractor::ActorStatus::Running);
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stopping_as_a_task_finishes_waits_for_it() {
+        let (task, release) = finishing_task();
+        while !task.canceller.is_closed() {
+            tokio::task::yield_now().await;
+        }
+        let mut state = CounterState {
+            prev: Some(task),
+            ..CounterState::default()
+        };
+
+        let stopping = tokio::spawn(async move {
+            state.stop().await.unwrap();
+            state
+        });
+        tokio::task::yield_now().await;
+        assert!(!stopping.is_finished());
+        release.send(()).unwrap();
+        let state = stopping.await.unwrap();
+        assert_eq!(state.stats().cancelled, 0);
+    }
+
+    #[tokio::test]
+    async fn stopping_after_a_task_finished_just_joins_it() {
+        let task = task_running(|_| async { Ok(()) });
+        while !task.handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        let mut state = CounterState {
+            prev: Some(task),
+            ..CounterState::default()
+        };
+
+        state.stop().await.unwrap();
+        assert_eq!(state.stats().cancelled, 0);
+    }
+
+    #[tokio::test]
+    async fn stopping_cancels_a_running_task() {
+        let task = task_running(|cancel| async move {
+            let _ = cancel.await;
+            Ok(())
+        });
+        let mut state = CounterState {
+            prev: Some(task),
+            ..CounterState::default()
+        };
+
+        let started = Instant::now();
+        state.stop().await.unwrap();
+        assert!(started.elapsed() < STOP_TIMEOUT);
+        assert_eq!(state.stats().cancelled, 1);
+    }
+
+    #[tokio::test]
+    async fn stopping_aborts_a_task_that_ignores_cancelling() {
+        let task = task_running(|cancel| async move {
+            let _cancel = cancel;
+            std::future::pending().await
+        });
+        let aborted = task.handle.abort_handle();
+        let mut state = CounterState {
+            prev: Some(task),
+            ..CounterState::default()
+        };
+
+        let started = Instant::now();
+        state.stop().await.unwrap();
+        assert!(started.elapsed() >= STOP_TIMEOUT);
+        tokio::task::yield_now().await;
+        assert!(aborted.is_finished());
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_task_says_so() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app.clone()),
+                ..CounterArgs::default()
+            },
+            WorkMode::Async,
+        );
+        state
+            .start_task(increment(3), state.transform.clone())
+            .await
+            .unwrap();
+        let task = state.prev.take().unwrap();
+        let id = task.id;
+
+        let err = state.cancel(task).await.unwrap().unwrap_err();
+        assert_eq!(
+            err,
+            BlockTaskError {
+                kind: BlockTaskErrorKind::Cancelled,
+                actor: COUNTER_ACTOR_NAME.to_string(),
+                context: format!("{} from 3", id),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("{} from 3 (counter): cancelled", id)
+        );
+        app.stop(None);
+    }
+
+    #[tokio::test]
+    async fn a_task_whose_app_is_gone_fails_to_cast() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, app_handle) = Actor::spawn(None, MockApp, received).await.unwrap();
+        app.stop(None);
+        app_handle.await.unwrap();
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app),
+                work_duration: Duration::from_millis(50),
+                ..CounterArgs::default()
+            },
+            WorkMode::Async,
+        );
+        state
+            .start_task(increment(3), state.transform.clone())
+            .await
+            .unwrap();
+
+        // The task fails on its first report.
+        let err = state
+            .prev
+            .take()
+            .unwrap()
+            .handle
+            .await
+            .unwrap()
+            .unwrap_err();
+        let BlockTaskErrorKind::CastFailed(reason) = &err.kind else {
+            panic!("expected a failed cast, got {:?}", err.kind);
+        };
+        assert_eq!(err.actor, APP_ACTOR_NAME);
+        assert_eq!(err.context, "sending AppMessage::UpdateProgress(10)");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "sending AppMessage::UpdateProgress(10) (app): cast failed: {}",
+                reason
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn a_task_ends_cleanly_when_the_app_stops_mid_flight() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, app_handle) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(100),
+                ..CounterArgs::default()
+            },
+            WorkMode::Blocking,
+        );
+        state
+            .start_task(increment(3), state.transform.clone())
+            .await
+            .unwrap();
+        let task = state.prev.take().unwrap();
+        while task.progress.load(Ordering::Relaxed) == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        app.stop(None);
+        app_handle.await.unwrap();
+        // Failing the next report, not panicking, so the join succeeds.
+        let err = task.handle.await.unwrap().unwrap_err();
+        assert!(matches!(err.kind, BlockTaskErrorKind::CastFailed(_)));
+        assert_eq!(state.status.get(), CounterStatus::Idle);
+        assert_eq!(state.stats().completed, 0);
+    }
+
+    #[tokio::test]
+    async fn cancelling_mid_step_takes_one_check_interval() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        // Steps of a second each, far longer than the check interval.
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_secs(10),
+                ..CounterArgs::default()
+            },
+            WorkMode::Blocking,
+        );
+        state
+            .start_task(increment(3), state.transform.clone())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let started = Instant::now();
+        state.cancel_prev().await.unwrap();
+        assert!(
+            started.elapsed() < Duration::from_millis(200),
+            "took {:?}",
+            started.elapsed()
+        );
+        assert_eq!(state.stats().cancelled, 1);
+        app.stop(None);
+    }
+
+    #[tokio::test]
+    async fn progress_rises_while_a_task_runs() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            AsyncCounter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(200),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+        assert_eq!(counter_handle.get_progress().await.unwrap(), None);
+
+        counter_handle.increment(0).await.unwrap();
+        // Everything reported while the task runs, polled every 25ms or so.
+        let mut seen = Vec::new();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while let Some(pct) = counter_handle.get_progress().await.unwrap() {
+                seen.push(pct);
+                tokio::time::sleep(Duration::from_millis(25)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(seen.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", seen);
+        assert!(seen.first() < seen.last(), "{:?}", seen);
+        assert_eq!(
+            wait_for_stats(&counter_handle, |stats| stats.completed == 1)
+                .await
+                .last_completed,
+            Some(1)
+        );
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn the_supervisor_reports_a_task_start_to_finish() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let status = SharedStatus::default();
+        let (supervisor, handle) = Actor::spawn(
+            None,
+            CounterSupervisor,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(200),
+                status: status.clone(),
+                namespace: Some("status".to_string()),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(supervisor.clone());
+        assert_eq!(counter_handle.status().await.unwrap(), CounterStatus::Idle);
+
+        counter_handle.increment(0).await.unwrap();
+        // Each status in turn, polled until the task is done.
+        let mut seen = vec![CounterStatus::Idle];
+        tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                let current = counter_handle.status().await.unwrap();
+                let kind = std::mem::discriminant(&current);
+                if std::mem::discriminant(seen.last().unwrap()) != kind {
+                    seen.push(current);
+                }
+                if seen.len() > 1 && current == CounterStatus::Idle {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(
+            matches!(
+                seen[..],
+                [
+                    CounterStatus::Idle,
+                    CounterStatus::Running { .. },
+                    CounterStatus::Idle
+                ]
+            ),
+            "{:?}",
+            seen
+        );
+        // What the supervisor answers with is the status it was given.
+        assert_eq!(status.get(), CounterStatus::Idle);
+        supervisor.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn increments_apply_the_configured_transform() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                transform: Arc::new(|x: u8| x + 10),
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(0).await.unwrap();
+        let stats = wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        assert_eq!(stats.last_completed, Some(10));
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_restarted_counter_finishes_the_interrupted_increment() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        // Crashes the first task, as a bug in the work might.
+        let crashed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let crash = crashed.clone();
+        let (supervisor, handle) = Actor::spawn(
+            None,
+            CounterSupervisor,
+            CounterArgs {
+                transform: Arc::new(move |x: u8| {
+                    if !crash.swap(true, Ordering::SeqCst) {
+                        panic!("on purpose");
+                    }
+                    x + 1
+                }),
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                namespace: Some("restart".to_string()),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter = CounterHandle::new(supervisor.clone());
+
+        counter.increment(4).await.unwrap();
+        // Answered by the supervisor, so safe to ask while the counter is
+        // down.
+        while !crashed.load(Ordering::SeqCst)
+            || counter.status().await.unwrap() != CounterStatus::Idle
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        // The counter only learns its task panicked from the next message,
+        // which fails it. That message is lost with it.
+        counter.increment(0).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !received
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|message| message.starts_with("AppMessage::UpdateCountWithMetadata(5,"))
+            {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("never resumed: {:?}", received.lock().unwrap()));
+        supervisor.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn the_counter_counts_each_kind_of_message() {
+        let (counter, handle) = Actor::spawn(None, Counter, CounterArgs::default())
+            .await
+            .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        for _ in 0..3 {
+            counter_handle
+                .set_work_duration(Duration::from_millis(10))
+                .await
+                .unwrap();
+        }
+        counter_handle.get_progress().await.unwrap();
+        let messages = counter_handle.get_stats().await.unwrap().messages;
+        assert_eq!(messages.get("SetWorkDuration"), 3);
+        assert_eq!(messages.get("GetProgress"), 1);
+        assert_eq!(messages.get("GetStats"), 1);
+        assert_eq!(messages.total(), 5);
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_new_work_duration_applies_from_the_next_task() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            AsyncCounter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(300),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(0).await.unwrap();
+        counter_handle
+            .set_work_duration(Duration::from_millis(10))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The running task keeps its 300ms.
+        assert_eq!(counter_handle.get_stats().await.unwrap().completed, 0);
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+
+        let started = Instant::now();
+        counter_handle.increment(1).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 2).await;
+        assert!(started.elapsed() < Duration::from_millis(150));
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+
+    /// Where `counter` ends up after the same few steps, each waited out.
+    async fn run_steps<A>(counter: A) -> Option<u8>
+    where
+        A: Actor<Msg = CounterMessage, Arguments = CounterArgs>,
+    {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(0).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        counter_handle
+            .increment_by(1, 3, "test".to_string(), "test".to_string())
+            .await
+            .unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 2).await;
+        counter_handle.decrement(4).await.unwrap();
+        let stats = wait_for_stats(&counter_handle, |stats| stats.completed == 3).await;
+        counter.stop(None);
+        handle.await.unwrap();
+        stats.last_completed
+    }
+
+    #[tokio::test]
+    async fn both_counters_end_up_at_the_same_value() {
+        assert_eq!(run_steps(Counter).await, Some(3));
+        assert_eq!(run_steps(AsyncCounter).await, Some(3));
+    }
+
+    /// Asks for an increment before the app is up, then has the app say
+    /// it's ready, returning what it was sent.
+    async fn increment_before_the_app() -> Vec<String> {
+        let (counter, handle) = Actor::spawn(
+            None,
+            AsyncCounter,
+            CounterArgs {
+                work_duration: Duration::ZERO,
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+        counter_handle.increment(1).await.unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+
+        counter_handle.app_ready(app.clone()).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        counter.stop(None);
+        handle.await.unwrap();
+        app.drain_and_wait(None).await.unwrap();
+        received.lock().unwrap().clone()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn work_asked_for_before_the_app_is_ready_waits_for_it() {
+        let mut runs = tokio::task::JoinSet::new();
+        for _ in 0..200 {
+            runs.spawn(increment_before_the_app());
+        }
+        while let Some(received) = runs.join_next().await {
+            let received = received.unwrap();
+            assert!(
+                received
+                    .iter()
+                    .any(|message| message.starts_with("AppMessage::UpdateCountWithMetadata(2,")),
+                "{:?}",
+                received
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn a_task_logs_under_the_handle_span_with_its_fields() {
+        // The blocking task logs from a thread of its own.
+        let logs = Logs::global();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+        // An unusual value, to pick this test's lines out from others'.
+        counter_handle.increment(217).await.unwrap();
+        wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        counter.stop(None);
+        handle.await.unwrap();
+
+        let logs = logs.text();
+        let line = logs
+            .lines()
+            .find(|line| line.contains("counter.current=217"))
+            .unwrap_or_else(|| panic!("no task span in:\n{}", logs));
+        assert!(
+            line.contains("counter.handle{counter.message=CounterMessage::Increment(217)}"),
+            "{}",
+            line
+        );
+        assert!(line.contains("counter.step=1"), "{}", line);
+        assert!(line.contains("counter.direction=Up"), "{}", line);
+    }
+
+    #[tokio::test]
+    async fn concurrent_tasks_log_under_their_own_ids() {
+        let (logs, _logging) = Logs::capture();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received).await.unwrap();
+        let mut counters = Vec::new();
+        for _ in 0..2 {
+            let (counter, handle) = Actor::spawn(
+                None,
+                AsyncCounter,
+                CounterArgs {
+                    app: Some(app.clone()),
+                    work_duration: Duration::from_millis(20),
+                    ..CounterArgs::default()
+                },
+            )
+            .await
+            .unwrap();
+            counters.push((CounterHandle::new(counter), handle));
+        }
+
+        for (counter, _) in &counters {
+            counter.increment(0).await.unwrap();
+        }
+        let mut ids = Vec::new();
+        for (counter, _) in &counters {
+            let stats = wait_for_stats(counter, |stats| stats.completed == 1).await;
+            ids.push(stats.latest_task.unwrap());
+        }
+        assert_ne!(ids[0], ids[1]);
+        let logs = logs.text();
+        for id in ids {
+            let finished = logs
+                .lines()
+                .filter(|line| line.contains("Finished waiting"))
+                .filter(|line| line.contains(&format!("task_id={}", id)))
+                .count();
+            assert_eq!(finished, 1, "{}", logs);
+        }
+        for (counter, handle) in counters {
+            counter.actor.stop(None);
+            handle.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn a_deep_queue_is_warned_about() {
+        let (logs, _logging) = Logs::capture();
+        let mut state = CounterState::default();
+        // Twenty messages forwarded by a supervisor before any is handled.
+        for _ in 0..20 {
+            state.mailbox.sent();
+        }
+
+        for _ in 0..20 {
+            state
+                .handle(CounterMessage::SetWorkDuration(Duration::ZERO))
+                .await
+                .unwrap();
+        }
+        assert_eq!(state.max_observed_depth, 20);
+        let logs = logs.text();
+        let warnings: Vec<_> = logs
+            .lines()
+            .filter(|line| line.contains("WARN") && line.contains("falling behind"))
+            .collect();
+        // Past the default threshold of ten, so for the first ten handled.
+        assert_eq!(warnings.len(), 10, "{}", logs);
+        assert!(
+            warnings[0].contains("20 messages queued"),
+            "{}",
+            warnings[0]
+        );
+        assert!(
+            warnings[9].contains("11 messages queued"),
+            "{}",
+            warnings[9]
+        );
+    }
+
+    #[tokio::test]
+    async fn the_app_is_told_the_queue_drains() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let mut state = CounterState::with_args(
+            CounterArgs {
+                app: Some(app.clone()),
+                ..CounterArgs::default()
+            },
+            WorkMode::Blocking,
+        );
+        for _ in 0..3 {
+            state.mailbox.sent();
+        }
+
+        for _ in 0..3 {
+            state
+                .handle(CounterMessage::SetWorkDuration(Duration::ZERO))
+                .await
+                .unwrap();
+        }
+        app.drain_and_wait(None).await.unwrap();
+        let received = received.lock().unwrap();
+        let depths: Vec<_> = received
+            .iter()
+            .filter(|message| message.starts_with("AppMessage::QueueDepth"))
+            .collect();
+        // Each not counting the one being handled.
+        assert_eq!(
+            depths,
+            [
+                "AppMessage::QueueDepth(2)",
+                "AppMessage::QueueDepth(1)",
+                "AppMessage::QueueDepth(0)",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn incrementing_at_255_finishes_without_an_update() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (app, _) = Actor::spawn(None, MockApp, received.clone()).await.unwrap();
+        let (counter, handle) = Actor::spawn(
+            None,
+            Counter,
+            CounterArgs {
+                app: Some(app.clone()),
+                work_duration: Duration::from_millis(10),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        let counter_handle = CounterHandle::new(counter.clone());
+
+        counter_handle.increment(u8::MAX).await.unwrap();
+        let stats = wait_for_stats(&counter_handle, |stats| stats.completed == 1).await;
+        assert_eq!(stats.last_completed, None);
+        counter.stop(None);
+        // A panic in the task would fail its join here.
+        handle.await.unwrap();
+        app.drain_and_wait(None).await.unwrap();
+        let received = received.lock().unwrap();
+        assert!(
+            received
+                .iter()
+                .any(|message| message == "AppMessage::UpdateProgress(100)")
+        );
+        assert!(
+            !received
+                .iter()
+                .any(|message| message.starts_with("AppMessage::UpdateCount")),
+            "{:?}",
+            received
+        );
+    }
 }
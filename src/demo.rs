@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use crate::script::ScriptCommand;
+
+/// How long each increment takes during the demo, kept under the gap between
+/// steps so every increment lands before the next step.
+pub const DEMO_WORK_DURATION: Duration = Duration::from_secs(1);
+
+/// The demo's commands, each run this long after the one before it.
+const DEMO_STEPS: [(Duration, ScriptCommand); 8] = [
+    (Duration::from_secs(1), ScriptCommand::Increment),
+    (Duration::from_millis(1500), ScriptCommand::Increment),
+    (Duration::from_millis(1500), ScriptCommand::Increment),
+    (Duration::from_millis(1500), ScriptCommand::Reset),
+    (Duration::from_secs(1), ScriptCommand::Increment),
+    (Duration::from_millis(1500), ScriptCommand::Increment),
+    (Duration::from_millis(1500), ScriptCommand::Decrement),
+    (Duration::from_secs(2), ScriptCommand::Quit),
+];
+
+/// A fixed run through the app for recordings, enabled with `DEMO=1`. The
+/// app checks for due steps on every tick, so steps run to the tick.
+#[derive(Debug)]
+pub struct Demo {
+    next: usize,
+    next_at: Instant,
+}
+
+impl Demo {
+    pub fn start(now: Instant) -> Self {
+        Self {
+            next: 0,
+            next_at: now + DEMO_STEPS[0].0,
+        }
+    }
+
+    /// Pushes the remaining steps back by `by`, for time the app spent not
+    /// playing them.
+    pub fn delay(&mut self, by: Duration) {
+        self.next_at += by;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == DEMO_STEPS.len()
+    }
+
+    /// The next step, if it's due by `now`.
+    pub fn next_due(&mut self, now: Instant) -> Option<ScriptCommand> {
+        let (_, command) = DEMO_STEPS.get(self.next).filter(|_| now >= self.next_at)?;
+        self.next += 1;
+        if let Some((delay, _)) = DEMO_STEPS.get(self.next) {
+            self.next_at += *delay;
+        }
+        Some(command.clone())
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+/// How many of the latest frames [`FrameTimings::average`] is taken over.
+pub const FRAME_WINDOW: usize = 30;
+
+/// How many messages of each variant an actor has handled, for spotting hot
+/// paths such as a runaway redraw loop.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageCounts(BTreeMap<String, u64>);
+
+impl MessageCounts {
+    /// Counts one more message of the variant `kind`.
+    pub fn record(&mut self, kind: &str) {
+        // Only a variant's first message allocates its key.
+        match self.0.get_mut(kind) {
+            Some(count) => *count += 1,
+            None => {
+                self.0.insert(kind.to_string(), 1);
+            }
+        }
+    }
+
+    pub fn get(&self, kind: &str) -> u64 {
+        self.0.get(kind).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    /// Every variant seen so far with its count, busiest first.
+    pub fn busiest(&self) -> Vec<(&str, u64)> {
+        let mut counts = self
+            .0
+            .iter()
+            .map(|(kind, &count)| (kind.as_str(), count))
+            .collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
+}
+
+/// How long the latest draws took, for judging how much redraw coalescing
+/// saves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameTimings {
+    // The last `FRAME_WINDOW` at most, oldest first.
+    recent: VecDeque<Duration>,
+    draws: u64,
+}
+
+impl FrameTimings {
+    pub fn record(&mut self, elapsed: Duration) {
+        if self.recent.len() == FRAME_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(elapsed);
+        self.draws += 1;
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.recent.back().copied()
+    }
+
+    /// Over the last [`FRAME_WINDOW`] frames, or as many as there have been.
+    pub fn average(&self) -> Option<Duration> {
+        let n = u32::try_from(self.recent.len()).ok().filter(|&n| n > 0)?;
+        Some(self.recent.iter().sum::<Duration>() / n)
+    }
+
+    /// Every draw so far, not just those in the window.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
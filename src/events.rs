@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use crossterm::event::EventStream;
+use futures::StreamExt;
+use ractor::{Actor, ActorRef, cast};
+
+use crate::AppMessage;
+
+/// How long to wait for a terminal event before checking the mailbox again.
+const EVENT_POLL: Duration = Duration::from_millis(250);
+
+/// Reads terminal events and casts each to the app as an
+/// [`AppMessage::HandleEvent`]. It keeps itself reading by casting itself
+/// [`EventStreamMessage::Next`] after every wait, which also gives a
+/// [`EventStreamMessage::Stop`] its turn.
+pub struct EventStreamActor;
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventStreamMessage {
+    Next,
+    Stop,
+}
+
+impl std::fmt::Display for EventStreamMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventStreamMessage::Next => write!(f, "EventStreamMessage::Next"),
+            EventStreamMessage::Stop => write!(f, "EventStreamMessage::Stop"),
+        }
+    }
+}
+
+pub struct EventStreamState {
+    app: ActorRef<AppMessage>,
+    events: EventStream,
+}
+
+impl Actor for EventStreamActor {
+    type Msg = EventStreamMessage;
+
+    type State = EventStreamState;
+
+    type Arguments = ActorRef<AppMessage>;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        app: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        cast!(myself, EventStreamMessage::Next)?;
+        Ok(EventStreamState {
+            app,
+            events: EventStream::new(),
+        })
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match message {
+            EventStreamMessage::Next => {
+                // The stream doesn't lose events to a dropped `next`.
+                match tokio::time::timeout(EVENT_POLL, state.events.next()).await {
+                    Err(_) => {}
+                    Ok(Some(event)) => cast!(state.app, AppMessage::HandleEvent(event?))?,
+                    Ok(None) => {
+                        // Nothing more will come in, so there's no way left to
+                        // drive the app.
+                        tracing::info!("Terminal events ended; quitting");
+                        cast!(state.app, AppMessage::Quit)?;
+                        myself.stop(None);
+                        return Ok(());
+                    }
+                }
+                cast!(myself, EventStreamMessage::Next)?;
+            }
+            EventStreamMessage::Stop => {
+                tracing::info!("Stopping terminal events");
+                myself.stop(None);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! A version of the ratatui counter example split into actors that
+//! communicate by message passing.
+
+pub mod app;
+pub mod audit;
+pub mod capabilities;
+// The serde derives on `CounterMessage` use its deprecated variants, and an
+// `allow` on the enum doesn't reach them.
+#[cfg_attr(feature = "recording", allow(deprecated))]
+pub mod counter;
+pub mod demo;
+pub mod diagnostics;
+pub mod events;
+pub mod logging;
+pub mod memory_pressure;
+pub mod mirror;
+pub mod modal;
+pub mod names;
+pub mod palette;
+pub mod panel;
+#[cfg(feature = "recording")]
+mod recording;
+pub mod replay;
+pub mod rpc;
+pub mod script;
+pub mod snapshot;
+pub mod spawn;
+pub mod strings;
+pub mod terminal;
+#[cfg(test)]
+mod testing;
+pub mod theme;
+pub mod ticker;
+pub mod toast;
+
+pub use app::{
+    App, AppArgs, AppHandle, AppMessage, AppSnapshot, AppStartupError, AppState, AppWidget,
+    DisplayMode, PauseReason, StartupTimeoutError, VerticalAlign, render_into,
+};
+pub use audit::{AuditEntry, AuditMessage, AuditTrail};
+pub use capabilities::Capabilities;
+pub use counter::{
+    AsyncCounter, BlockTaskError, BlockTaskErrorKind, Checkpoint, Counter, CounterArgs,
+    CounterHandle, CounterMessage, CounterStats, CounterStatus, CounterSupervisor, MailboxDepth,
+    SharedStatus, TaskId,
+};
+pub use diagnostics::{FrameTimings, MessageCounts};
+pub use logging::{LogConfig, Rotation};
+pub use memory_pressure::MemoryPressureLevel;
+pub use mirror::{Mirror, MirrorMessage};
+pub use panel::{Panel, PanelId};
+pub use spawn::{ActorRefs, Config, spawn_all};
+pub use strings::Strings;
+pub use theme::Theme;
+pub use ticker::{Ticker, TickerArgs, TickerMessage};
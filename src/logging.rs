@@ -0,0 +1,86 @@
+//! Where the app's logs go.
+
+use std::{io::Write, path::PathBuf};
+
+use anyhow::{Context, Result};
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+
+/// How often a new log file is started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    /// One file, appended to across runs.
+    Never,
+}
+
+impl std::str::FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minutely" => Ok(Rotation::Minutely),
+            "hourly" => Ok(Rotation::Hourly),
+            "daily" => Ok(Rotation::Daily),
+            "never" => Ok(Rotation::Never),
+            _ => Err(format!("unknown rotation {:?}", s)),
+        }
+    }
+}
+
+/// Log files are named after `prefix` in `dir`, with the date and time
+/// they were started appended unless `rotation` is [`Rotation::Never`]. A
+/// `dir` of `-` logs to stderr instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub rotation: Rotation,
+}
+
+impl Default for LogConfig {
+    /// Daily files in the temporary directory, out of the way of whatever
+    /// directory the app was started in.
+    fn default() -> Self {
+        Self {
+            dir: std::env::temp_dir(),
+            prefix: "tui".to_string(),
+            rotation: Rotation::default(),
+        }
+    }
+}
+
+impl LogConfig {
+    /// Opens where the logs should be written.
+    pub fn writer(&self) -> Result<Box<dyn Write + Send>> {
+        if self.dir.as_os_str() == "-" {
+            return Ok(Box::new(std::io::stderr()));
+        }
+        Ok(match self.rotation {
+            Rotation::Minutely => Box::new(rolling::minutely(&self.dir, &self.prefix)),
+            Rotation::Hourly => Box::new(rolling::hourly(&self.dir, &self.prefix)),
+            Rotation::Daily => Box::new(rolling::daily(&self.dir, &self.prefix)),
+            Rotation::Never => {
+                let path = self.dir.join(&self.prefix);
+                std::fs::create_dir_all(&self.dir)
+                    .with_context(|| format!("Couldn't create {}", self.dir.display()))?;
+                let file = std::fs::File::options()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .with_context(|| format!("Couldn't open {}", path.display()))?;
+                Box::new(file)
+            }
+        })
+    }
+
+    /// Sends the `tracing` output to [`LogConfig::writer`] from a
+    /// background thread. Logs are flushed when the guard is dropped.
+    pub fn init(&self) -> Result<WorkerGuard> {
+        let (writer, guard) = tracing_appender::non_blocking(self.writer()?);
+        tracing_subscriber::fmt().with_writer(writer).init();
+        Ok(guard)
+    }
+}
@@ -1,45 +1,250 @@
-mod app;
-mod counter;
-
-use anyhow::Result;
-use counter::Counter;
-use crossterm::event::{self, Event, KeyEventKind};
-use ractor::{Actor, call, cast};
-
-use app::{App, AppArgs, AppMessage};
-
-#[tokio::main]
-async fn main() -> Result<()> {
-    let (nb, _guard) =
-        tracing_appender::non_blocking(tracing_appender::rolling::daily("./", "tui"));
-    tracing_subscriber::fmt().with_writer(nb).init();
-    let terminal = ratatui::init();
-    let (app, app_handle) =
-        Actor::spawn(Some("app".to_string()), App, AppArgs { tui: terminal }).await?;
-
-    let (counter, counter_handle) = Actor::spawn(Some("counter".to_string()), Counter, ()).await?;
-
-    while !call!(app, AppMessage::ShouldExit)? {
-        cast!(app, AppMessage::Draw)?;
-        match event::read()? {
-            // it's important to check that the event is a key press event as
-            // crossterm also emits key release and repeat events on Windows.
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                tracing::info!("Got a key event {:?}", key_event);
-                cast!(app, AppMessage::HandleKey(key_event))?;
-                tracing::info!("Fired a key event {:?}", key_event);
-            }
-            _ => {}
-        };
-    }
-    tracing::info!("Stopping app actor");
-    app.stop(None);
-    counter.stop(None);
-    tracing::info!("Exited, awaiting handle");
-    app_handle.await?;
-    counter_handle.await?;
-    tracing::info!("Handle ended");
-    ratatui::restore();
-    tracing::info!("Terminal restored");
-    Ok(())
-}
+use std::{io::IsTerminal, time::Duration};
+
+use anyhow::{Context, Result};
+use ractor::{Actor, cast};
+use tokio::sync::watch;
+
+use ratatui_ractor::{
+    ActorRefs, AppHandle, Capabilities, Config, CounterArgs, LogConfig, Strings, Theme,
+    VerticalAlign,
+    app::{DEFAULT_REDRAW_WINDOW, DEFAULT_RESET_CONFIRM_ABOVE},
+    counter::DEFAULT_WORK_DURATION,
+    demo::DEMO_WORK_DURATION,
+    events::{EventStreamActor, EventStreamMessage},
+    script::{ScriptArgs, ScriptReader},
+    spawn_all,
+    terminal::{TerminalGuard, TerminalMode},
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut log = LogConfig::default();
+    if let Some(dir) =
+        std::env::args().find_map(|arg| arg.strip_prefix("--log-dir=").map(str::to_string))
+    {
+        log.dir = dir.into();
+    }
+    if let Some(rotation) =
+        std::env::args().find_map(|arg| arg.strip_prefix("--log-rotation=").map(str::to_string))
+    {
+        log.rotation = rotation.parse().map_err(anyhow::Error::msg)?;
+    }
+    let capabilities = Capabilities::detect();
+    let terminal_mode = TerminalMode {
+        alt_screen: !std::env::args().any(|arg| arg == "--no-alt-screen"),
+        focus_changes: capabilities.focus_reporting,
+    };
+    // Before the terminal is taken over, so a bad value is readable.
+    let align =
+        match std::env::args().find_map(|arg| arg.strip_prefix("--align=").map(str::to_string)) {
+            Some(align) => align.parse().map_err(anyhow::Error::msg)?,
+            None => VerticalAlign::default(),
+        };
+    let redraw_window = match std::env::args()
+        .find_map(|arg| arg.strip_prefix("--redraw-window=").map(str::to_string))
+    {
+        Some(ms) => Duration::from_millis(
+            ms.parse()
+                .map_err(|e| anyhow::anyhow!("Bad --redraw-window {:?}: {}", ms, e))?,
+        ),
+        None => DEFAULT_REDRAW_WINDOW,
+    };
+    let reset_confirm_above = match std::env::args().find_map(|arg| {
+        arg.strip_prefix("--confirm-reset-above=")
+            .map(str::to_string)
+    }) {
+        Some(n) => n
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Bad --confirm-reset-above {:?}: {}", n, e))?,
+        None => DEFAULT_RESET_CONFIRM_ABOVE,
+    };
+    let cancel_confirm_above = std::env::args()
+        .find_map(|arg| {
+            arg.strip_prefix("--confirm-cancel-above=")
+                .map(str::to_string)
+        })
+        .map(|pct| {
+            pct.parse::<u8>()
+                .ok()
+                .filter(|&pct| pct <= 100)
+                .ok_or_else(|| anyhow::anyhow!("Bad --confirm-cancel-above {:?}", pct))
+        })
+        .transpose()?;
+    let theme =
+        match std::env::args().find_map(|arg| arg.strip_prefix("--theme=").map(str::to_string)) {
+            Some(path) => Theme::load(&path),
+            None => Ok(Theme::default()),
+        };
+    let strings =
+        match std::env::args().find_map(|arg| arg.strip_prefix("--strings=").map(str::to_string)) {
+            Some(path) => Strings::load(&path),
+            None => Ok(Strings::default()),
+        };
+    // Terminals this basic can't be relied on for box-drawing glyphs.
+    let ascii_borders = std::env::args().any(|arg| arg == "--ascii-borders")
+        || std::env::var("TERM").is_ok_and(|term| term == "dumb" || term.starts_with("vt"));
+    let reduce_motion = std::env::args().any(|arg| arg == "--reduce-motion");
+    let show_sparkline = !std::env::args().any(|arg| arg == "--no-sparkline");
+    let demo = std::env::var("DEMO").is_ok_and(|demo| demo == "1");
+    // Report what a session would run with and stop there, without touching
+    // the terminal. Here a bad theme or string set is an error rather than a
+    // warning.
+    if std::env::args().any(|arg| arg == "--check") {
+        let theme = theme?;
+        let strings = strings?;
+        println!("capabilities:   {:?}", capabilities);
+        println!("alt screen:     {}", terminal_mode.alt_screen);
+        println!("align:          {:?}", align);
+        println!("redraw window:  {:?}", redraw_window);
+        println!("confirm reset:  above {}", reset_confirm_above);
+        println!("confirm cancel: {:?}", cancel_confirm_above);
+        println!("theme:          {:?}", theme);
+        println!("strings:        {:?}", strings);
+        println!("ascii borders:  {}", ascii_borders);
+        println!("reduce motion:  {}", reduce_motion);
+        println!("sparkline:      {}", show_sparkline);
+        println!("demo:           {}", demo);
+        println!("log:            {:?}", log);
+        return Ok(());
+    }
+    // Only once it's known this is a session, which `--check` isn't, so
+    // checking doesn't leave log files behind.
+    let _guard = log.init()?;
+    // A bad theme shouldn't stop the app; it's only colors.
+    let theme = theme.unwrap_or_else(|e| {
+        tracing::warn!("Using the default theme: {:#}", e);
+        Theme::default()
+    });
+    // Nor should bad strings; English will do.
+    let strings = strings.unwrap_or_else(|e| {
+        tracing::warn!("Using the default strings: {:#}", e);
+        Strings::default()
+    });
+    let terminal = ratatui_ractor::terminal::init(terminal_mode)?;
+    // Any `?` from here on still restores the terminal on the way out.
+    let terminal_guard = TerminalGuard::new(terminal_mode);
+    let ActorRefs {
+        app: app_ref,
+        app_handle,
+        counter,
+        counter_handle,
+        ticker,
+        ticker_handle,
+        audit,
+        audit_handle,
+        mirror,
+        mirror_handle,
+    } = spawn_all(
+        terminal,
+        Config {
+            counter: CounterArgs {
+                work_duration: if demo {
+                    DEMO_WORK_DURATION
+                } else {
+                    DEFAULT_WORK_DURATION
+                },
+                ..CounterArgs::default()
+            },
+            reduce_motion,
+            show_sparkline,
+            demo,
+            align,
+            redraw_window,
+            ascii_borders,
+            theme,
+            strings,
+            capabilities,
+            reset_confirm_above,
+            cancel_confirm_above,
+            ..Config::default()
+        },
+    )
+    .await?;
+    #[cfg(all(feature = "memory-pressure", target_os = "linux"))]
+    let (memory_pressure, memory_pressure_handle) = Actor::spawn(
+        None,
+        ratatui_ractor::memory_pressure::MemoryPressureActor,
+        ratatui_ractor::memory_pressure::MemoryPressureArgs {
+            app: app_ref.clone(),
+            ticker: ticker.clone(),
+            pressure_file: None,
+        },
+    )
+    .await
+    .context("Failed to spawn the memory pressure monitor")?;
+    let (events, events_handle) = Actor::spawn(None, EventStreamActor, app_ref.clone())
+        .await
+        .context("Failed to spawn the event stream actor")?;
+    let app = AppHandle::new(app_ref);
+    // Keys come from the terminal either way, so piped input is free to
+    // carry a control script.
+    let script = if std::io::stdin().is_terminal() {
+        None
+    } else {
+        Some(
+            Actor::spawn(None, ScriptReader, ScriptArgs::stdin(app.clone()))
+                .await
+                .context("Failed to spawn the script reader")?
+                .0,
+        )
+    };
+
+    // Set once the app stops for any reason, including a failed handler, so
+    // the loop below doesn't wait on a dead actor.
+    let (shutdown, stopped) = watch::channel(false);
+    let app_watch = tokio::spawn(async move {
+        let stopped = app_handle.await;
+        let _ = shutdown.send(true);
+        stopped
+    });
+
+    let result = app.wait_for_exit(stopped).await;
+    if let Err(e) = &result {
+        tracing::error!("Main loop failed: {}", e);
+    }
+    // Shut down from the outside in, each stage finished before the next:
+    // first whatever sends the app work, then the counter, so a task
+    // finishing late lands on a running app, then the app itself. Once it's
+    // gone nothing can draw, so restoring the terminal after is final.
+    tracing::info!("Stopping inputs");
+    #[cfg(all(feature = "memory-pressure", target_os = "linux"))]
+    memory_pressure.stop(None);
+    let _ = cast!(events, EventStreamMessage::Stop);
+    if let Some(script) = &script {
+        script.stop(None);
+    }
+    ticker.stop(None);
+    // A failure at one stage mustn't skip the stops after it.
+    let inputs_stopped = async {
+        #[cfg(all(feature = "memory-pressure", target_os = "linux"))]
+        memory_pressure_handle.await?;
+        ticker_handle.await?;
+        events_handle.await?;
+        anyhow::Ok(())
+    }
+    .await;
+    tracing::info!("Stopping counter");
+    counter.stop(None);
+    let counter_stopped = counter_handle.await;
+    tracing::info!("Stopping app actor");
+    app.stop();
+    let app_stopped = app_watch.await;
+    audit.stop(None);
+    mirror.stop(None);
+    let audit_stopped = audit_handle.await;
+    let mirror_stopped = mirror_handle.await;
+    let stopped = (|| {
+        inputs_stopped?;
+        counter_stopped?;
+        app_stopped??;
+        audit_stopped?;
+        mirror_stopped?;
+        anyhow::Ok(())
+    })();
+    tracing::info!("Handle ended");
+    // Restore the terminal no matter how we got here.
+    drop(terminal_guard);
+    tracing::info!("Terminal restored");
+    result?;
+    stopped
+}
@@ -0,0 +1,282 @@
+/// How hard the system is working to find memory, as reported by the
+/// kernel's pressure stall information.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryPressureLevel {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for MemoryPressureLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryPressureLevel::Low => write!(f, "low"),
+            MemoryPressureLevel::Medium => write!(f, "medium"),
+            MemoryPressureLevel::High => write!(f, "high"),
+        }
+    }
+}
+
+#[cfg(all(feature = "memory-pressure", target_os = "linux"))]
+pub use monitor::{MemoryPressureActor, MemoryPressureArgs, MemoryPressureMessage};
+
+#[cfg(all(feature = "memory-pressure", target_os = "linux"))]
+mod monitor {
+    use std::{path::PathBuf, time::Duration};
+
+    use ractor::{Actor, ActorRef, cast, concurrency::JoinHandle};
+
+    use super::MemoryPressureLevel;
+    use crate::{AppMessage, TickerMessage, ticker::DEFAULT_TICK_INTERVAL};
+
+    /// How often the pressure files are read.
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// The tick interval while pressure is high.
+    const HIGH_PRESSURE_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Percentages of the last ten seconds in which some task stalled on
+    /// memory, at or above which pressure counts as medium and high.
+    const MEDIUM_THRESHOLD: f64 = 5.0;
+    const HIGH_THRESHOLD: f64 = 20.0;
+
+    /// The cgroup's own accounting first, so a container sees its limit
+    /// rather than the host's.
+    const PRESSURE_FILES: [&str; 2] = ["/sys/fs/cgroup/memory.pressure", "/proc/pressure/memory"];
+
+    /// Watches memory pressure, slowing the [`Ticker`](crate::Ticker) while
+    /// it's high and telling the app whenever the level changes.
+    pub struct MemoryPressureActor;
+
+    #[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+    pub enum MemoryPressureMessage {
+        Check,
+    }
+
+    impl std::fmt::Display for MemoryPressureMessage {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                MemoryPressureMessage::Check => write!(f, "MemoryPressureMessage::Check"),
+            }
+        }
+    }
+
+    pub struct MemoryPressureArgs {
+        pub app: ActorRef<AppMessage>,
+        pub ticker: ActorRef<TickerMessage>,
+        /// Read instead of the kernel's files when given.
+        pub pressure_file: Option<PathBuf>,
+    }
+
+    pub struct MemoryPressureState {
+        app: ActorRef<AppMessage>,
+        ticker: ActorRef<TickerMessage>,
+        path: Option<PathBuf>,
+        level: MemoryPressureLevel,
+        timer: JoinHandle<()>,
+    }
+
+    /// Parses the `some avg10=` figure out of a pressure file.
+    fn some_avg10(contents: &str) -> Option<f64> {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix("some "))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    }
+
+    fn level_for(avg10: f64) -> MemoryPressureLevel {
+        if avg10 >= HIGH_THRESHOLD {
+            MemoryPressureLevel::High
+        } else if avg10 >= MEDIUM_THRESHOLD {
+            MemoryPressureLevel::Medium
+        } else {
+            MemoryPressureLevel::Low
+        }
+    }
+
+    impl Actor for MemoryPressureActor {
+        type Msg = MemoryPressureMessage;
+
+        type State = MemoryPressureState;
+
+        type Arguments = MemoryPressureArgs;
+
+        async fn pre_start(
+            &self,
+            myself: ActorRef<Self::Msg>,
+            args: Self::Arguments,
+        ) -> Result<Self::State, ractor::ActorProcessingErr> {
+            let path = args.pressure_file.or_else(|| {
+                PRESSURE_FILES.iter().map(PathBuf::from).find(|path| {
+                    std::fs::read_to_string(path).is_ok_and(|c| some_avg10(&c).is_some())
+                })
+            });
+            match &path {
+                Some(path) => tracing::info!("Watching memory pressure in {}", path.display()),
+                None => tracing::warn!("No memory pressure information available"),
+            }
+            Ok(MemoryPressureState {
+                app: args.app,
+                ticker: args.ticker,
+                path,
+                level: MemoryPressureLevel::Low,
+                timer: myself.send_interval(POLL_INTERVAL, || MemoryPressureMessage::Check),
+            })
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            message: Self::Msg,
+            state: &mut Self::State,
+        ) -> Result<(), ractor::ActorProcessingErr> {
+            match message {
+                MemoryPressureMessage::Check => {
+                    let Some(avg10) = state
+                        .path
+                        .as_ref()
+                        .and_then(|path| std::fs::read_to_string(path).ok())
+                        .and_then(|contents| some_avg10(&contents))
+                    else {
+                        return Ok(());
+                    };
+                    let level = level_for(avg10);
+                    if level == state.level {
+                        return Ok(());
+                    }
+                    tracing::info!("Memory pressure is now {} ({:.2}%)", level, avg10);
+                    let was_high = state.level == MemoryPressureLevel::High;
+                    let is_high = level == MemoryPressureLevel::High;
+                    if is_high != was_high {
+                        let interval = if is_high {
+                            HIGH_PRESSURE_TICK_INTERVAL
+                        } else {
+                            DEFAULT_TICK_INTERVAL
+                        };
+                        cast!(state.ticker, TickerMessage::SetInterval(interval))?;
+                    }
+                    cast!(state.app, AppMessage::SetMemoryPressure(level))?;
+                    state.level = level;
+                }
+            }
+            Ok(())
+        }
+
+        async fn post_stop(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            state: &mut Self::State,
+        ) -> Result<(), ractor::ActorProcessingErr> {
+            state.timer.abort();
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            marker::PhantomData,
+            sync::{Arc, Mutex},
+        };
+
+        use super::*;
+
+        /// Stands in for the ticker or the app, noting each message it's sent.
+        struct Recorder<M>(PhantomData<fn() -> M>);
+
+        impl<M: std::fmt::Display + ractor::Message> Actor for Recorder<M> {
+            type Msg = M;
+            type State = Arc<Mutex<Vec<String>>>;
+            type Arguments = Arc<Mutex<Vec<String>>>;
+
+            async fn pre_start(
+                &self,
+                _myself: ActorRef<Self::Msg>,
+                received: Self::Arguments,
+            ) -> Result<Self::State, ractor::ActorProcessingErr> {
+                Ok(received)
+            }
+
+            async fn handle(
+                &self,
+                _myself: ActorRef<Self::Msg>,
+                message: Self::Msg,
+                received: &mut Self::State,
+            ) -> Result<(), ractor::ActorProcessingErr> {
+                received.lock().unwrap().push(message.to_string());
+                Ok(())
+            }
+        }
+
+        fn pressure(avg10: f64) -> String {
+            format!(
+                "some avg10={:.2} avg60=0.00 avg300=0.00 total=0\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n",
+                avg10
+            )
+        }
+
+        #[tokio::test]
+        async fn high_pressure_slows_the_ticker_until_it_eases() {
+            let file = std::env::temp_dir()
+                .join(format!("ratatui-ractor-pressure-{}", std::process::id()));
+            std::fs::write(&file, pressure(0.0)).unwrap();
+            let (ticks, apps) = Default::default();
+            let (ticker, _) = Actor::spawn(None, Recorder(PhantomData), Arc::clone(&ticks))
+                .await
+                .unwrap();
+            let (app, _) = Actor::spawn(None, Recorder(PhantomData), Arc::clone(&apps))
+                .await
+                .unwrap();
+            let (monitor, handle) = Actor::spawn(
+                None,
+                MemoryPressureActor,
+                MemoryPressureArgs {
+                    app: app.clone(),
+                    ticker: ticker.clone(),
+                    pressure_file: Some(file.clone()),
+                },
+            )
+            .await
+            .unwrap();
+            // Each level change reaches the app, so wait for that before
+            // writing the next reading over this one.
+            for (i, avg10) in [50.0, 10.0, 1.0].into_iter().enumerate() {
+                std::fs::write(&file, pressure(avg10)).unwrap();
+                cast!(monitor, MemoryPressureMessage::Check).unwrap();
+                while apps.lock().unwrap().len() <= i {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+            monitor.drain_and_wait(None).await.unwrap();
+            handle.await.unwrap();
+            std::fs::remove_file(&file).unwrap();
+            ticker.drain_and_wait(None).await.unwrap();
+            app.drain_and_wait(None).await.unwrap();
+            // Slowed for high, and back once it's gone, with no change in
+            // between.
+            assert_eq!(
+                *ticks.lock().unwrap(),
+                [
+                    format!(
+                        "TickerMessage::SetInterval({:?})",
+                        HIGH_PRESSURE_TICK_INTERVAL
+                    ),
+                    format!("TickerMessage::SetInterval({:?})", DEFAULT_TICK_INTERVAL),
+                ]
+            );
+            assert_eq!(
+                *apps.lock().unwrap(),
+                [
+                    "AppMessage::SetMemoryPressure(high)",
+                    "AppMessage::SetMemoryPressure(medium)",
+                    "AppMessage::SetMemoryPressure(low)",
+                ]
+            );
+        }
+    }
+}
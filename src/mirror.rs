@@ -0,0 +1,108 @@
+use ractor::{Actor, ActorRef, RpcReplyPort, cast};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::names::namespaced;
+
+/// The process group the app broadcasts each new counter value to, under
+/// the app's namespace if it has one.
+pub const COUNTER_UPDATES: &str = "counter_updates";
+
+/// Keeps a read-only copy of the counter's value, fed by the app's
+/// broadcasts, so it can be shown somewhere other than the app's own frame.
+pub struct Mirror;
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum MirrorMessage {
+    UpdateCount(u8),
+    #[cfg_attr(
+        feature = "recording",
+        serde(
+            skip_deserializing,
+            serialize_with = "crate::recording::serialize_reply_port"
+        )
+    )]
+    GetValue(RpcReplyPort<u8>),
+}
+
+impl std::fmt::Display for MirrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MirrorMessage::UpdateCount(value) => write!(f, "MirrorMessage::UpdateCount({})", value),
+            MirrorMessage::GetValue(_) => write!(f, "MirrorMessage::GetValue"),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize))]
+pub struct MirrorState {
+    value: u8,
+}
+
+impl Widget for &MirrorState {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(Line::from(vec![
+            "Value: ".into(),
+            self.value.to_string().yellow(),
+        ]))
+        .centered()
+        .block(Block::bordered().title(" Mirror "))
+        .render(area, buf);
+    }
+}
+
+/// Sends `value` to every member of [`COUNTER_UPDATES`] in `namespace`.
+pub(crate) fn broadcast_count(namespace: Option<&str>, value: u8) {
+    for member in ractor::pg::get_members(&namespaced(namespace, COUNTER_UPDATES)) {
+        let mirror: ActorRef<MirrorMessage> = member.into();
+        if let Err(e) = cast!(mirror, MirrorMessage::UpdateCount(value)) {
+            tracing::warn!("Failed to broadcast count: {}", e);
+        }
+    }
+}
+
+impl Actor for Mirror {
+    type Msg = MirrorMessage;
+
+    type State = MirrorState;
+
+    /// The namespace of the app to mirror, if it has one.
+    type Arguments = Option<String>;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        namespace: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        // Membership ends on its own when we stop.
+        ractor::pg::join(
+            namespaced(namespace.as_deref(), COUNTER_UPDATES),
+            vec![myself.get_cell()],
+        );
+        Ok(MirrorState::default())
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match message {
+            MirrorMessage::UpdateCount(value) => {
+                tracing::info!("Mirrored counter update: {}", value);
+                state.value = value;
+            }
+            MirrorMessage::GetValue(reply) => {
+                reply.send(state.value)?;
+            }
+        }
+        Ok(())
+    }
+}
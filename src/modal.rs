@@ -0,0 +1,129 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+
+/// How wide a modal is, at most.
+const MODAL_WIDTH: u16 = 50;
+
+/// What a confirmation dialog does once confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModalAction {
+    Reset,
+    /// Increments or decrements the counter, cancelling the running task.
+    Increment,
+    Decrement,
+    Quit,
+}
+
+/// A dialog that takes over the keyboard until it's accepted or dismissed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modal {
+    pub title: String,
+    pub text: String,
+    buttons: Vec<String>,
+    selected: usize,
+    // Run when the last button is accepted.
+    action: Option<ModalAction>,
+}
+
+impl Modal {
+    /// A modal with the first of `buttons` selected, or a lone "OK" if there
+    /// are none.
+    pub fn new(title: String, text: String, mut buttons: Vec<String>) -> Self {
+        if buttons.is_empty() {
+            buttons.push("OK".to_string());
+        }
+        Self {
+            title,
+            text,
+            buttons,
+            selected: 0,
+            action: None,
+        }
+    }
+
+    /// A dialog asking whether to go ahead with `action`, which `confirm`
+    /// does. Cancel comes first and starts selected, so a stray Enter is
+    /// harmless.
+    pub fn confirm(title: String, text: String, confirm: String, action: ModalAction) -> Self {
+        Self {
+            action: Some(action),
+            ..Self::new(title, text, vec!["Cancel".to_string(), confirm])
+        }
+    }
+
+    /// Renames a [`Modal::confirm`] dialog's Cancel button.
+    pub fn with_cancel(mut self, cancel: String) -> Self {
+        if self.is_confirm() {
+            self.buttons[0] = cancel;
+        }
+        self
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.buttons.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.buttons.len() - 1) % self.buttons.len();
+    }
+
+    pub fn selected(&self) -> &str {
+        &self.buttons[self.selected]
+    }
+
+    /// Whether this is a [`Modal::confirm`] dialog, which Y and N answer.
+    pub fn is_confirm(&self) -> bool {
+        self.action.is_some()
+    }
+
+    /// Selects the button that goes ahead with the action.
+    pub fn select_confirm(&mut self) {
+        self.selected = self.buttons.len() - 1;
+    }
+
+    /// What accepting the selected button does, beyond closing the dialog.
+    pub fn accepted_action(&self) -> Option<ModalAction> {
+        self.action
+            .filter(|_| self.selected == self.buttons.len() - 1)
+    }
+}
+
+impl Widget for &Modal {
+    /// Renders centered in `area`, over whatever is there.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut buttons = Line::default();
+        for (i, button) in self.buttons.iter().enumerate() {
+            let button = Span::from(format!(" {} ", button));
+            buttons.push_span(if i == self.selected {
+                button.reversed()
+            } else {
+                button
+            });
+            buttons.push_span(" ");
+        }
+        let [overlay] = Layout::vertical([Constraint::Length(6)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [overlay] = Layout::horizontal([Constraint::Length(MODAL_WIDTH)])
+            .flex(Flex::Center)
+            .areas(overlay);
+        Clear.render(overlay, buf);
+        let block = Block::bordered().title(format!(" {} ", self.title).bold());
+        let inner = block.inner(overlay);
+        block.render(overlay, buf);
+        let [text_area, buttons_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(inner);
+        Paragraph::new(self.text.as_str())
+            .centered()
+            .wrap(Wrap { trim: true })
+            .render(text_area, buf);
+        buttons.centered().render(buttons_area, buf);
+    }
+}
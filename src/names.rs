@@ -0,0 +1,18 @@
+//! The names actors register under, for finding them with
+//! [`ractor::registry::where_is`]. Instances given a namespace register
+//! under [`namespaced`] names instead.
+
+pub const APP_ACTOR_NAME: &str = "app";
+/// The counter the [`crate::CounterSupervisor`] runs, not the supervisor.
+pub const COUNTER_ACTOR_NAME: &str = "counter";
+pub const AUDIT_ACTOR_NAME: &str = "audit";
+pub const MIRROR_ACTOR_NAME: &str = "mirror";
+
+/// `name` as registered under `namespace`, `"<namespace>:<name>"`, so that
+/// several instances can share a process; `name` alone without one.
+pub fn namespaced(namespace: Option<&str>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{}:{}", namespace, name),
+        None => name.to_string(),
+    }
+}
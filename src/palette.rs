@@ -0,0 +1,156 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Clear, List, Widget},
+};
+
+/// How wide the palette is, at most.
+const PALETTE_WIDTH: u16 = 40;
+
+/// Something the palette can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaletteAction {
+    Increment,
+    Decrement,
+    Reset,
+    /// Opens the prompt for a value, as `=` does.
+    SetValue,
+    /// Opens the prompt for a value to watch for, as `w` does.
+    Watch,
+    ToggleSync,
+    TogglePause,
+    ToggleFreeze,
+    ToggleMessageCounts,
+    Help,
+    Quit,
+}
+
+impl PaletteAction {
+    /// Every action, in the order listed.
+    pub const ALL: [PaletteAction; 11] = [
+        PaletteAction::Increment,
+        PaletteAction::Decrement,
+        PaletteAction::Reset,
+        PaletteAction::SetValue,
+        PaletteAction::Watch,
+        PaletteAction::ToggleSync,
+        PaletteAction::TogglePause,
+        PaletteAction::ToggleFreeze,
+        PaletteAction::ToggleMessageCounts,
+        PaletteAction::Help,
+        PaletteAction::Quit,
+    ];
+
+    /// The key that does the same from normal mode, if there is one.
+    pub fn key(self) -> Option<KeyCode> {
+        match self {
+            PaletteAction::Increment => Some(KeyCode::Right),
+            PaletteAction::Decrement => Some(KeyCode::Left),
+            PaletteAction::Reset => None,
+            PaletteAction::SetValue => Some(KeyCode::Char('=')),
+            PaletteAction::Watch => Some(KeyCode::Char('w')),
+            PaletteAction::ToggleSync => Some(KeyCode::Char('s')),
+            PaletteAction::TogglePause => Some(KeyCode::Char('p')),
+            PaletteAction::ToggleFreeze => Some(KeyCode::F(5)),
+            PaletteAction::ToggleMessageCounts => Some(KeyCode::Char('d')),
+            PaletteAction::Help => Some(KeyCode::Char('?')),
+            PaletteAction::Quit => Some(KeyCode::Char('q')),
+        }
+    }
+
+    /// The name the palette lists and filters on.
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteAction::Increment => "Increment",
+            PaletteAction::Decrement => "Decrement",
+            PaletteAction::Reset => "Reset",
+            PaletteAction::SetValue => "Set value",
+            PaletteAction::Watch => "Watch for a value",
+            PaletteAction::ToggleSync => "Toggle sync increments",
+            PaletteAction::TogglePause => "Pause/resume",
+            PaletteAction::ToggleFreeze => "Freeze/unfreeze",
+            PaletteAction::ToggleMessageCounts => "Toggle message counts",
+            PaletteAction::Help => "Help",
+            PaletteAction::Quit => "Quit",
+        }
+    }
+}
+
+/// A searchable list of actions, narrowed by typing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    filter: String,
+    selected: usize,
+}
+
+impl Palette {
+    /// The actions whose label contains the filter, ignoring case.
+    pub fn matches(&self) -> Vec<PaletteAction> {
+        let filter = self.filter.to_lowercase();
+        PaletteAction::ALL
+            .into_iter()
+            .filter(|action| action.label().to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// Narrows the list, starting again from its first match.
+    pub fn push(&mut self, c: char) {
+        self.filter.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.filter.pop();
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
+    }
+
+    /// The highlighted action, if anything matches.
+    pub fn selected(&self) -> Option<PaletteAction> {
+        self.matches().get(self.selected).copied()
+    }
+}
+
+impl Widget for &Palette {
+    /// Renders near the top of `area`, over whatever is there.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let matches = self.matches();
+        let items = matches.iter().enumerate().map(|(i, action)| {
+            let line = Line::from(format!(" {} ", action.label()));
+            if i == self.selected {
+                line.reversed()
+            } else {
+                line
+            }
+        });
+        // At least one row, so an empty list still reads as a list.
+        let height = matches.len().max(1) as u16 + 2;
+        let [_, overlay] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(height)]).areas(area);
+        let [overlay] = Layout::horizontal([Constraint::Length(PALETTE_WIDTH)])
+            .flex(Flex::Center)
+            .areas(overlay);
+        Clear.render(overlay, buf);
+        List::new(items)
+            .block(Block::bordered().title(format!(" :{}_ ", self.filter).bold()))
+            .render(overlay, buf);
+    }
+}
@@ -0,0 +1,40 @@
+/// Identifies a panel, so it can be removed without holding the `Panel`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct PanelId(pub String);
+
+/// A section of the layout. Active panels split the space inside the main
+/// block evenly, side by side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum Panel {
+    CounterPanel,
+    LogPanel,
+    StatsPanel,
+    ActorListPanel,
+    /// A user-defined panel, drawn as an empty titled box.
+    Custom(PanelId),
+}
+
+impl Panel {
+    pub fn id(&self) -> PanelId {
+        match self {
+            Panel::CounterPanel => PanelId("counter".to_string()),
+            Panel::LogPanel => PanelId("log".to_string()),
+            Panel::StatsPanel => PanelId("stats".to_string()),
+            Panel::ActorListPanel => PanelId("actors".to_string()),
+            Panel::Custom(id) => id.clone(),
+        }
+    }
+
+    /// The built-in panel toggled by a number key, if any.
+    pub fn for_key(n: u32) -> Option<Panel> {
+        match n {
+            1 => Some(Panel::CounterPanel),
+            2 => Some(Panel::LogPanel),
+            3 => Some(Panel::StatsPanel),
+            4 => Some(Panel::ActorListPanel),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use ractor::{ActorRef, RpcReplyPort};
+use serde::{Serialize, Serializer};
+
+/// Reply ports can't cross a serialization boundary, so messages carrying
+/// one record a placeholder instead. Such messages can't be deserialized.
+pub(crate) fn serialize_reply_port<T, S: Serializer>(
+    _port: &RpcReplyPort<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str("<reply port>")
+}
+
+/// Like reply ports, actor refs are only meaningful in this process; a
+/// message naming an actor records the name and the actor's ID.
+pub(crate) fn serialize_named_actor<T, S: Serializer>(
+    name: &String,
+    actor: &ActorRef<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    (name, actor.get_id().to_string()).serialize(serializer)
+}
+
+/// A sequence number alongside a reply port keeps the number.
+pub(crate) fn serialize_events_since<T, S: Serializer>(
+    seq: &u64,
+    _port: &RpcReplyPort<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    (seq, "<reply port>").serialize(serializer)
+}
+
+/// An actor ref on its own records just the actor's ID.
+pub(crate) fn serialize_actor<T, S: Serializer>(
+    actor: &ActorRef<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&actor.get_id().to_string())
+}
@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+/// How many changes are kept for replay; a client further behind than this
+/// has to start over from the current value.
+pub const REPLAY_CAPACITY: usize = 1024;
+
+/// A change to the counter's value. Each carries the whole new value, so the
+/// latest change is enough to catch up, but the order is kept for clients
+/// that show the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReplayOp {
+    Update(u8),
+    Reset,
+}
+
+impl ReplayOp {
+    /// The value after this change.
+    pub fn value(self) -> u8 {
+        match self {
+            ReplayOp::Update(new) => new,
+            ReplayOp::Reset => 0,
+        }
+    }
+}
+
+/// A change along with its place in the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplayEvent {
+    /// Starts at 1 and goes up by one per change.
+    pub seq: u64,
+    pub op: ReplayOp,
+}
+
+/// The latest changes to the counter, in order, for a client that lost track
+/// of the value to catch up with.
+#[derive(Debug, Default)]
+pub struct ReplayLog {
+    last_seq: u64,
+    events: VecDeque<ReplayEvent>,
+}
+
+impl ReplayLog {
+    pub fn push(&mut self, op: ReplayOp) {
+        if self.events.len() == REPLAY_CAPACITY {
+            self.events.pop_front();
+        }
+        self.last_seq += 1;
+        self.events.push_back(ReplayEvent {
+            seq: self.last_seq,
+            op,
+        });
+    }
+
+    /// Every kept change after `seq`, oldest first. If the first one isn't
+    /// `seq + 1`, changes in between have been dropped.
+    pub fn since(&self, seq: u64) -> Vec<ReplayEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.seq > seq)
+            .copied()
+            .collect()
+    }
+}
+
+/// The value after `events`, starting from `value`.
+pub fn replay(value: u8, events: &[ReplayEvent]) -> u8 {
+    events.last().map_or(value, |event| event.op.value())
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+/// How long [`call_timeout!`](crate::call_timeout) waits for a reply unless
+/// given a timeout.
+pub const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `call_timeout!`: [`ractor::call!`] with a timeout, so a hung actor can't
+/// block the caller forever.
+///
+/// * `$actor` - The actor to call
+/// * `$msg` - The message builder, as for [`ractor::call!`]
+/// * `$timeout` - An optional [`Duration`], defaulting to
+///   [`DEFAULT_CALL_TIMEOUT`]
+///
+/// Returns the reply, or [`ractor::RactorErr::Timeout`] (logged as a warning)
+/// if none arrives in time.
+#[macro_export]
+macro_rules! call_timeout {
+    ($actor:expr, $msg:expr) => {
+        $crate::call_timeout!($actor, $msg, $crate::rpc::DEFAULT_CALL_TIMEOUT)
+    };
+    ($actor:expr, $msg:expr, $timeout:expr) => {{
+        let timeout: ::std::time::Duration = $timeout;
+        let result = ::ractor::call_t!($actor, $msg, timeout.as_millis() as u64);
+        if let Err(::ractor::RactorErr::Timeout) = &result {
+            $crate::rpc::warn_timeout(stringify!($msg), timeout);
+        }
+        result
+    }};
+}
+
+#[doc(hidden)]
+pub fn warn_timeout(message: &str, timeout: Duration) {
+    tracing::warn!("No reply to {} within {:?}", message, timeout);
+}
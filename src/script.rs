@@ -0,0 +1,200 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::PathBuf,
+};
+
+use ractor::{Actor, ActorRef, cast};
+
+use crate::AppHandle;
+
+/// A line of a control script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScriptCommand {
+    Increment,
+    Decrement,
+    Reset,
+    Set(u8),
+    Title(String),
+    Pause,
+    Resume,
+    /// Writes every change to the counter still kept for replay, a value per
+    /// line and oldest first, to the given file.
+    Export(PathBuf),
+    Quit,
+}
+
+impl std::str::FromStr for ScriptCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (command, arg) = match line.trim().split_once(' ') {
+            Some((command, arg)) => (command, Some(arg.trim())),
+            None => (line.trim(), None),
+        };
+        match (command, arg) {
+            ("inc", None) => Ok(ScriptCommand::Increment),
+            ("dec", None) => Ok(ScriptCommand::Decrement),
+            ("reset", None) => Ok(ScriptCommand::Reset),
+            ("set", Some(value)) => value
+                .parse()
+                .map(ScriptCommand::Set)
+                .map_err(|e| format!("bad value {:?}: {}", value, e)),
+            ("title", Some(title)) => Ok(ScriptCommand::Title(title.to_string())),
+            ("pause", None) => Ok(ScriptCommand::Pause),
+            ("resume", None) => Ok(ScriptCommand::Resume),
+            ("export", Some(path)) => Ok(ScriptCommand::Export(path.into())),
+            ("quit", None) => Ok(ScriptCommand::Quit),
+            _ => Err(format!("unknown command {:?}", line.trim())),
+        }
+    }
+}
+
+/// Drives the app from commands read a line at a time from its input (`inc`,
+/// `dec`, `reset`, `set 10`, `title Hello`, `pause`, `resume`,
+/// `export out.txt`, `quit`), for piping in a script or driving the TUI from
+/// a test harness. Blank lines and lines
+/// starting with `#` are skipped. Stops at the end of input.
+pub struct ScriptReader;
+
+pub struct ScriptArgs {
+    pub app: AppHandle,
+    /// Read on a thread of its own until it ends.
+    pub input: Box<dyn Read + Send>,
+}
+
+impl ScriptArgs {
+    pub fn stdin(app: AppHandle) -> Self {
+        Self {
+            app,
+            input: Box::new(std::io::stdin()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScriptMessage {
+    Line(String),
+    Eof,
+}
+
+impl std::fmt::Display for ScriptMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptMessage::Line(line) => write!(f, "ScriptMessage::Line({:?})", line),
+            ScriptMessage::Eof => write!(f, "ScriptMessage::Eof"),
+        }
+    }
+}
+
+impl Actor for ScriptReader {
+    type Msg = ScriptMessage;
+
+    type State = AppHandle;
+
+    type Arguments = ScriptArgs;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        ScriptArgs { app, input }: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        // A plain thread rather than a blocking task: a read stuck waiting on
+        // stdin would otherwise hold up the runtime's shutdown.
+        std::thread::spawn(move || {
+            for line in BufReader::new(input).lines() {
+                let Ok(line) = line else { break };
+                if cast!(myself, ScriptMessage::Line(line)).is_err() {
+                    return;
+                }
+            }
+            let _ = cast!(myself, ScriptMessage::Eof);
+        });
+        Ok(app)
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        app: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        let line = match message {
+            ScriptMessage::Line(line) => line,
+            ScriptMessage::Eof => {
+                tracing::info!("End of script");
+                myself.stop(None);
+                return Ok(());
+            }
+        };
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            return Ok(());
+        }
+        let command = match line.parse() {
+            Ok(command) => command,
+            Err(e) => {
+                tracing::warn!("Skipping script line: {}", e);
+                return Ok(());
+            }
+        };
+        app.run(command).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::{Config, CounterArgs, spawn_all};
+
+    #[tokio::test]
+    async fn a_script_drives_the_counter() {
+        let refs = spawn_all(
+            Terminal::new(TestBackend::new(80, 20)).unwrap(),
+            Config {
+                counter: CounterArgs {
+                    work_duration: Duration::from_millis(100),
+                    ..CounterArgs::default()
+                },
+                namespace: Some("script".to_string()),
+                ..Config::default()
+            },
+        )
+        .await
+        .unwrap();
+        let app = AppHandle::new(refs.app.clone());
+        let script = "# Two increments, which go as one\ninc\n\nbogus\ninc\ntitle Scripted\n";
+        let (_, reader) = Actor::spawn(
+            None,
+            ScriptReader,
+            ScriptArgs {
+                app: app.clone(),
+                input: Box::new(script.as_bytes()),
+            },
+        )
+        .await
+        .unwrap();
+        reader.await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while app.get_counter().await.unwrap() != 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the script's increments never landed");
+        let snapshot = app.snapshot().await.unwrap();
+        assert_eq!(snapshot.title, "Scripted");
+        assert_eq!(snapshot.history, [2]);
+
+        refs.ticker.stop(None);
+        refs.counter.stop(None);
+        refs.app.stop(None);
+        refs.audit.stop(None);
+        refs.mirror.stop(None);
+    }
+}
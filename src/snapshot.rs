@@ -0,0 +1,82 @@
+use std::fmt::Write;
+
+use ratatui::buffer::{Buffer, Cell};
+
+/// How many differing cells [`diff_buffers`] lists before summarizing.
+const MAX_LISTED: usize = 20;
+
+/// Compares two buffers cell by cell, returning a readable description of
+/// every difference, or `None` if they match. Meant for assertion messages
+/// in rendering tests:
+///
+/// ```
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// # let expected = Buffer::empty(Rect::new(0, 0, 1, 1));
+/// # let actual = expected.clone();
+/// if let Some(diff) = ratatui_ractor::snapshot::diff_buffers(&expected, &actual) {
+///     panic!("{}", diff);
+/// }
+/// ```
+pub fn diff_buffers(expected: &Buffer, actual: &Buffer) -> Option<String> {
+    if expected.area != actual.area {
+        return Some(format!(
+            "buffer areas differ: expected {:?}, got {:?}",
+            expected.area, actual.area
+        ));
+    }
+    let area = expected.area;
+    let diffs = area
+        .positions()
+        .filter_map(|pos| {
+            let (want, got) = (&expected[pos], &actual[pos]);
+            (want != got).then(|| describe(pos.x, pos.y, want, got))
+        })
+        .collect::<Vec<_>>();
+    if diffs.is_empty() {
+        return None;
+    }
+
+    let mut out = format!("buffers differ in {} cell(s):\n", diffs.len());
+    for diff in diffs.iter().take(MAX_LISTED) {
+        let _ = writeln!(out, "  {}", diff);
+    }
+    if diffs.len() > MAX_LISTED {
+        let _ = writeln!(out, "  ... and {} more", diffs.len() - MAX_LISTED);
+    }
+    out.push_str("expected:\n");
+    push_rows(&mut out, expected);
+    out.push_str("actual:\n");
+    push_rows(&mut out, actual);
+    Some(out)
+}
+
+fn describe(x: u16, y: u16, want: &Cell, got: &Cell) -> String {
+    if want.symbol() == got.symbol() {
+        format!(
+            "({}, {}): {:?} style {:?}, got {:?}",
+            x,
+            y,
+            want.symbol(),
+            want.style(),
+            got.style()
+        )
+    } else {
+        format!(
+            "({}, {}): expected {:?}, got {:?}",
+            x,
+            y,
+            want.symbol(),
+            got.symbol()
+        )
+    }
+}
+
+/// Appends the buffer's text, one quoted row per line, ignoring styles.
+fn push_rows(out: &mut String, buf: &Buffer) {
+    for y in buf.area.top()..buf.area.bottom() {
+        let row = (buf.area.left()..buf.area.right())
+            .map(|x| buf[(x, y)].symbol())
+            .collect::<String>();
+        let _ = writeln!(out, "  {:?}", row);
+    }
+}
@@ -0,0 +1,165 @@
+//! Spawning the actors an app needs, wired to each other, in one call.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ractor::{Actor, ActorRef};
+use ratatui::{Terminal, prelude::Backend};
+use tokio::task::JoinHandle;
+
+use crate::{
+    App, AppArgs, AppMessage, AuditMessage, AuditTrail, Capabilities, CounterArgs, CounterHandle,
+    CounterMessage, CounterSupervisor, Mirror, MirrorMessage, Strings, Theme, Ticker, TickerArgs,
+    TickerMessage, VerticalAlign,
+    app::{DEFAULT_REDRAW_WINDOW, DEFAULT_RESET_CONFIRM_ABOVE, DEFAULT_STARTUP_TIMEOUT},
+    names::{APP_ACTOR_NAME, AUDIT_ACTOR_NAME, MIRROR_ACTOR_NAME, namespaced},
+    ticker::DEFAULT_TICK_INTERVAL,
+};
+
+/// Everything [`spawn_all`] needs besides the terminal. The app's settings
+/// mean what they do on [`AppArgs`].
+#[derive(Clone)]
+pub struct Config {
+    /// The app starts with the counter's work duration.
+    pub counter: CounterArgs,
+    pub tick_interval: Duration,
+    pub reduce_motion: bool,
+    pub show_sparkline: bool,
+    pub align: VerticalAlign,
+    pub demo: bool,
+    pub redraw_window: Duration,
+    pub startup_timeout: Duration,
+    pub ascii_borders: bool,
+    pub theme: Theme,
+    pub strings: Strings,
+    pub capabilities: Capabilities,
+    pub reset_confirm_above: u8,
+    pub cancel_confirm_above: Option<u8>,
+    /// Every actor registers under this namespace, as
+    /// [`crate::names::namespaced`] does, so that several sets can run in
+    /// one process. It replaces any namespace on `counter`.
+    pub namespace: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            counter: CounterArgs::default(),
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            reduce_motion: false,
+            show_sparkline: true,
+            align: VerticalAlign::default(),
+            demo: false,
+            redraw_window: DEFAULT_REDRAW_WINDOW,
+            startup_timeout: DEFAULT_STARTUP_TIMEOUT,
+            ascii_borders: false,
+            theme: Theme::default(),
+            strings: Strings::default(),
+            capabilities: Capabilities::default(),
+            reset_confirm_above: DEFAULT_RESET_CONFIRM_ABOVE,
+            cancel_confirm_above: None,
+            namespace: None,
+        }
+    }
+}
+
+/// The actors [`spawn_all`] started, each with the handle that finishes
+/// when it stops.
+pub struct ActorRefs {
+    pub app: ActorRef<AppMessage>,
+    pub app_handle: JoinHandle<()>,
+    /// The [`CounterSupervisor`], which the app talks to as its counter.
+    pub counter: ActorRef<CounterMessage>,
+    pub counter_handle: JoinHandle<()>,
+    pub ticker: ActorRef<TickerMessage>,
+    pub ticker_handle: JoinHandle<()>,
+    pub audit: ActorRef<AuditMessage>,
+    pub audit_handle: JoinHandle<()>,
+    pub mirror: ActorRef<MirrorMessage>,
+    pub mirror_handle: JoinHandle<()>,
+}
+
+/// Spawns the audit trail, mirror, counter, app and ticker, registering
+/// those that are looked up by name under the names in [`crate::names`],
+/// in the config's namespace if it has one.
+/// The app draws to `terminal`, which should already be set up, as
+/// [`crate::terminal::init`] does; a [`ratatui::backend::TestBackend`] runs
+/// everything without one. Keyboard input is left to the caller, who
+/// decides where it comes from.
+pub async fn spawn_all<B>(terminal: Terminal<B>, config: Config) -> Result<ActorRefs>
+where
+    B: Backend + Send + std::fmt::Debug + 'static,
+{
+    let namespace = config.namespace.as_deref();
+    let (audit, audit_handle) = Actor::spawn(
+        Some(namespaced(namespace, AUDIT_ACTOR_NAME)),
+        AuditTrail,
+        (),
+    )
+    .await
+    .context("Failed to spawn the audit actor")?;
+    let (mirror, mirror_handle) = Actor::spawn(
+        Some(namespaced(namespace, MIRROR_ACTOR_NAME)),
+        Mirror,
+        config.namespace.clone(),
+    )
+    .await
+    .context("Failed to spawn the mirror actor")?;
+    let work_duration = config.counter.work_duration;
+    let (counter, counter_handle) = Actor::spawn(
+        None,
+        CounterSupervisor,
+        CounterArgs {
+            namespace: config.namespace.clone(),
+            ..config.counter
+        },
+    )
+    .await
+    .context("Failed to spawn the counter supervisor")?;
+    let (app, app_handle) = Actor::spawn(
+        Some(namespaced(namespace, APP_ACTOR_NAME)),
+        App,
+        AppArgs {
+            tui: Box::new(terminal),
+            reduce_motion: config.reduce_motion,
+            counter: CounterHandle::new(counter.clone()),
+            work_duration,
+            show_sparkline: config.show_sparkline,
+            demo: config.demo,
+            align: config.align,
+            redraw_window: config.redraw_window,
+            startup_timeout: config.startup_timeout,
+            ascii_borders: config.ascii_borders,
+            theme: config.theme,
+            strings: config.strings,
+            capabilities: config.capabilities,
+            reset_confirm_above: config.reset_confirm_above,
+            cancel_confirm_above: config.cancel_confirm_above,
+            namespace: config.namespace.clone(),
+        },
+    )
+    .await
+    .context("Failed to spawn the app actor")?;
+    let (ticker, ticker_handle) = Actor::spawn(
+        None,
+        Ticker,
+        TickerArgs {
+            app: app.clone(),
+            interval: config.tick_interval,
+        },
+    )
+    .await
+    .context("Failed to spawn the ticker")?;
+    Ok(ActorRefs {
+        app,
+        app_handle,
+        counter,
+        counter_handle,
+        ticker,
+        ticker_handle,
+        audit,
+        audit_handle,
+        mirror,
+        mirror_handle,
+    })
+}
@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+use anyhow::{Context, Result};
+
+/// The words the app draws with, for showing it in another language.
+/// Labels are given without their trailing colon, and titles without the
+/// spaces either side of them in a border.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct Strings {
+    /// In the top border until replaced with `SetTitle`.
+    pub title: String,
+    pub value: String,
+    pub rate: String,
+    pub work: String,
+    /// In the stats panel.
+    pub min_max: String,
+    pub avg: String,
+    /// Labels the value being typed after `W`.
+    pub watch_for: String,
+    /// The instructions line's names for its keys.
+    pub decrement: String,
+    pub increment: String,
+    pub set: String,
+    pub pause: String,
+    pub help: String,
+    pub quit: String,
+    /// The names for keys while typing a value, in a dialog, or in the
+    /// palette. `cancel` is also a confirmation's button for not going
+    /// ahead.
+    pub commit: String,
+    pub cancel: String,
+    pub watch: String,
+    pub close: String,
+    pub choose: String,
+    pub accept: String,
+    pub dismiss: String,
+    pub filter: String,
+    pub run: String,
+    /// Panel and overlay titles.
+    pub log: String,
+    pub stats: String,
+    pub keys: String,
+    /// Over everything while the user has paused.
+    pub paused: String,
+    /// Flashed when Left or Right has nowhere left to go.
+    pub at_min: String,
+    pub at_max: String,
+    /// The reset confirmation's title and button.
+    pub reset: String,
+    /// `{value}` is replaced with the value that would be reset.
+    pub reset_prompt: String,
+    pub cancel_task: String,
+    /// `{pct}` is replaced with how far along the task is.
+    pub cancel_prompt: String,
+    pub cancel_confirm: String,
+    /// Asked on quitting with unsaved changes, under the `quit` title.
+    pub unsaved_prompt: String,
+    /// What the help overlay says each key does, by the key as it's listed
+    /// there, such as `<Tab>`. Keys left out are described in English.
+    pub descriptions: BTreeMap<String, String>,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self {
+            title: "Counter App Tutorial".to_string(),
+            value: "Value".to_string(),
+            rate: "Rate".to_string(),
+            work: "Work".to_string(),
+            min_max: "Min/max".to_string(),
+            avg: "Avg".to_string(),
+            watch_for: "Watch for".to_string(),
+            decrement: "Decrement".to_string(),
+            increment: "Increment".to_string(),
+            set: "Set".to_string(),
+            pause: "Pause".to_string(),
+            help: "Help".to_string(),
+            quit: "Quit".to_string(),
+            commit: "Commit".to_string(),
+            cancel: "Cancel".to_string(),
+            watch: "Watch".to_string(),
+            close: "Close".to_string(),
+            choose: "Choose".to_string(),
+            accept: "Accept".to_string(),
+            dismiss: "Dismiss".to_string(),
+            filter: "Filter".to_string(),
+            run: "Run".to_string(),
+            log: "Log".to_string(),
+            stats: "Stats".to_string(),
+            keys: "Keys".to_string(),
+            paused: "PAUSED".to_string(),
+            at_min: "Already at 0".to_string(),
+            at_max: "Already at 255".to_string(),
+            reset: "Reset".to_string(),
+            reset_prompt: "Reset the counter from {value} to 0?".to_string(),
+            cancel_task: "Cancel task".to_string(),
+            cancel_prompt: "The running task is {pct}% done. Cancel it?".to_string(),
+            cancel_confirm: "Cancel it".to_string(),
+            unsaved_prompt: "Unsaved changes — quit anyway? [y/n]".to_string(),
+            descriptions: BTreeMap::new(),
+        }
+    }
+}
+
+impl Strings {
+    /// Reads a string set; see [`Strings::from_str`] for the format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Strings> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read strings {}", path.display()))?;
+        text.parse()
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Bad strings {}", path.display()))
+    }
+
+    /// The translation of an instruction given in English, or the English if
+    /// it has none.
+    pub fn instruction<'a>(&'a self, english: &'a str) -> &'a str {
+        match english {
+            "Decrement" => &self.decrement,
+            "Increment" => &self.increment,
+            "Set" => &self.set,
+            "Pause" => &self.pause,
+            "Help" => &self.help,
+            "Quit" => &self.quit,
+            _ => english,
+        }
+    }
+
+    /// What `key` does, or `english` if the set doesn't say.
+    pub fn description<'a>(&'a self, key: &str, english: &'a str) -> &'a str {
+        self.descriptions.get(key).map_or(english, String::as_str)
+    }
+}
+
+/// The text of a `= "text"` assignment, quoted or not.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Parses `name = "text"` lines, as a theme file does, with `#` comments
+/// and blank lines ignored. A key's description is given as
+/// `key "<Tab>" = "text"`, quoted since some keys have an `=` in them.
+/// Strings left out stay in English.
+impl FromStr for Strings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut strings = Strings::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("key ") {
+                let (key, value) = rest
+                    .trim_start()
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.split_once('"'))
+                    .and_then(|(key, rest)| Some((key, rest.trim_start().strip_prefix('=')?)))
+                    .ok_or_else(|| {
+                        format!("line {}: expected `key \"<key>\" = \"text\"`", i + 1)
+                    })?;
+                strings
+                    .descriptions
+                    .insert(key.to_string(), unquote(value).to_string());
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `name = \"text\"`", i + 1))?;
+            let slot = match name.trim() {
+                "title" => &mut strings.title,
+                "value" => &mut strings.value,
+                "rate" => &mut strings.rate,
+                "work" => &mut strings.work,
+                "min_max" => &mut strings.min_max,
+                "avg" => &mut strings.avg,
+                "watch_for" => &mut strings.watch_for,
+                "decrement" => &mut strings.decrement,
+                "increment" => &mut strings.increment,
+                "set" => &mut strings.set,
+                "pause" => &mut strings.pause,
+                "help" => &mut strings.help,
+                "quit" => &mut strings.quit,
+                "commit" => &mut strings.commit,
+                "cancel" => &mut strings.cancel,
+                "watch" => &mut strings.watch,
+                "close" => &mut strings.close,
+                "choose" => &mut strings.choose,
+                "accept" => &mut strings.accept,
+                "dismiss" => &mut strings.dismiss,
+                "filter" => &mut strings.filter,
+                "run" => &mut strings.run,
+                "log" => &mut strings.log,
+                "stats" => &mut strings.stats,
+                "keys" => &mut strings.keys,
+                "paused" => &mut strings.paused,
+                "at_min" => &mut strings.at_min,
+                "at_max" => &mut strings.at_max,
+                "reset" => &mut strings.reset,
+                "reset_prompt" => &mut strings.reset_prompt,
+                "cancel_task" => &mut strings.cancel_task,
+                "cancel_prompt" => &mut strings.cancel_prompt,
+                "cancel_confirm" => &mut strings.cancel_confirm,
+                "unsaved_prompt" => &mut strings.unsaved_prompt,
+                other => return Err(format!("line {}: no string named {:?}", i + 1, other)),
+            };
+            *slot = unquote(value).to_string();
+        }
+        Ok(strings)
+    }
+}
@@ -0,0 +1,244 @@
+use std::io::{self, stdout};
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{DisableFocusChange, EnableFocusChange},
+    execute,
+    terminal::{
+        self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
+        enable_raw_mode,
+    },
+};
+use ratatui::{
+    DefaultTerminal, Frame, Terminal,
+    layout::{Rect, Size},
+    prelude::{Backend, CrosstermBackend},
+};
+
+/// Something the app can draw to: a [`Terminal`] on any backend, behind a
+/// trait object so the app needn't be generic over which.
+pub trait DrawTarget: Send + std::fmt::Debug {
+    /// As [`Terminal::draw`], returning the area drawn.
+    fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect>;
+
+    fn clear(&mut self) -> io::Result<()>;
+
+    fn autoresize(&mut self) -> io::Result<()>;
+
+    fn size(&self) -> io::Result<Size>;
+}
+
+impl<B: Backend + Send + std::fmt::Debug> DrawTarget for Terminal<B> {
+    fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<Rect> {
+        Terminal::draw(self, |frame| render(frame)).map(|frame| frame.area)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        Terminal::clear(self)
+    }
+
+    fn autoresize(&mut self) -> io::Result<()> {
+        Terminal::autoresize(self)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        Terminal::size(self)
+    }
+}
+
+/// How the terminal was set up, so restoring it undoes exactly that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalMode {
+    /// Draws in the alternate screen, leaving the shell's screen untouched.
+    /// Without it the final frame stays in the scrollback after exit.
+    pub alt_screen: bool,
+    /// Has the terminal report focus changes; off for terminals that would
+    /// print the request instead.
+    pub focus_changes: bool,
+}
+
+impl Default for TerminalMode {
+    fn default() -> Self {
+        Self {
+            alt_screen: true,
+            focus_changes: true,
+        }
+    }
+}
+
+/// One thing done to the terminal in setting it up or restoring it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    EnableRawMode,
+    DisableRawMode,
+    EnableFocusChange,
+    DisableFocusChange,
+    EnterAlternateScreen,
+    LeaveAlternateScreen,
+    ClearScreen,
+    /// Moves to a new line below the last frame.
+    MoveBelowFrame,
+}
+
+impl Step {
+    fn run(self) -> io::Result<()> {
+        match self {
+            Step::EnableRawMode => enable_raw_mode(),
+            Step::DisableRawMode => disable_raw_mode(),
+            Step::EnableFocusChange => execute!(stdout(), EnableFocusChange),
+            Step::DisableFocusChange => execute!(stdout(), DisableFocusChange),
+            Step::EnterAlternateScreen => execute!(stdout(), EnterAlternateScreen),
+            Step::LeaveAlternateScreen => execute!(stdout(), LeaveAlternateScreen),
+            Step::ClearScreen => execute!(stdout(), Clear(ClearType::All)),
+            Step::MoveBelowFrame => {
+                let (_, rows) = terminal::size()?;
+                execute!(stdout(), MoveTo(0, rows.saturating_sub(1)))?;
+                println!();
+                Ok(())
+            }
+        }
+    }
+}
+
+/// What [`init`] does to the terminal for `mode`, in order.
+fn init_steps(mode: TerminalMode) -> Vec<Step> {
+    let mut steps = vec![Step::EnableRawMode];
+    if mode.focus_changes {
+        steps.push(Step::EnableFocusChange);
+    }
+    if mode.alt_screen {
+        steps.push(Step::EnterAlternateScreen);
+    } else {
+        // Start from a blank screen rather than drawing over the shell's.
+        steps.push(Step::ClearScreen);
+    }
+    steps
+}
+
+/// What [`restore`] does to the terminal for `mode`, in order.
+fn restore_steps(mode: TerminalMode) -> Vec<Step> {
+    let mut steps = Vec::new();
+    if mode.focus_changes {
+        steps.push(Step::DisableFocusChange);
+    }
+    steps.push(Step::DisableRawMode);
+    steps.push(if mode.alt_screen {
+        Step::LeaveAlternateScreen
+    } else {
+        // Leave the shell prompt below the last frame instead of over it.
+        Step::MoveBelowFrame
+    });
+    steps
+}
+
+/// Like [`ratatui::init`], but only entering the alternate screen if `mode`
+/// asks for it, and with focus changes reported if it asks for those. Panics
+/// restore the terminal to match.
+pub fn init(mode: TerminalMode) -> io::Result<DefaultTerminal> {
+    set_panic_hook(mode);
+    for step in init_steps(mode) {
+        step.run()?;
+    }
+    Terminal::new(CrosstermBackend::new(stdout()))
+}
+
+/// Like [`ratatui::restore`], undoing only what [`init`] did for `mode`.
+pub fn restore(mode: TerminalMode) {
+    if let Err(err) = try_restore(mode) {
+        eprintln!("Failed to restore terminal: {err}");
+    }
+}
+
+pub fn try_restore(mode: TerminalMode) -> io::Result<()> {
+    restore_steps(mode).into_iter().try_for_each(Step::run)
+}
+
+/// Restores the terminal when dropped, so returning early from anywhere
+/// between [`init`] and the end of a session still leaves it usable.
+#[must_use = "the terminal is restored as soon as the guard is dropped"]
+pub struct TerminalGuard {
+    mode: TerminalMode,
+}
+
+impl TerminalGuard {
+    pub fn new(mode: TerminalMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore(self.mode);
+    }
+}
+
+fn set_panic_hook(mode: TerminalMode) {
+    chain_panic_hook(move || restore(mode));
+}
+
+/// Has panics run `before` and then whatever hook was already set, which
+/// prints the message to a terminal `before` has restored.
+fn chain_panic_hook(before: impl Fn() + Send + Sync + 'static) {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        before();
+        hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+
+    #[test]
+    fn the_alternate_screen_is_entered_and_left() {
+        let mode = TerminalMode::default();
+        assert_eq!(
+            init_steps(mode),
+            [
+                Step::EnableRawMode,
+                Step::EnableFocusChange,
+                Step::EnterAlternateScreen
+            ]
+        );
+        assert_eq!(
+            restore_steps(mode),
+            [
+                Step::DisableFocusChange,
+                Step::DisableRawMode,
+                Step::LeaveAlternateScreen
+            ]
+        );
+    }
+
+    #[test]
+    fn no_alt_screen_draws_over_the_main_screen() {
+        let mode = TerminalMode {
+            alt_screen: false,
+            focus_changes: false,
+        };
+        assert_eq!(init_steps(mode), [Step::EnableRawMode, Step::ClearScreen]);
+        assert_eq!(
+            restore_steps(mode),
+            [Step::DisableRawMode, Step::MoveBelowFrame]
+        );
+    }
+
+    #[test]
+    fn a_panic_restores_the_terminal() {
+        let restored = Arc::new(AtomicBool::new(false));
+        let flag = restored.clone();
+        chain_panic_hook(move || flag.store(true, Ordering::SeqCst));
+
+        let result = std::thread::spawn(|| panic!("on purpose")).join();
+        // Back to the default hook, so later panics don't touch the flag.
+        let _ = std::panic::take_hook();
+        assert!(result.is_err());
+        assert!(restored.load(Ordering::SeqCst));
+    }
+}
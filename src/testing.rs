@@ -0,0 +1,57 @@
+//! Helpers shared by the unit tests.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Collects what's logged on this thread, for a test on a single-threaded
+/// runtime to read back.
+#[derive(Clone, Default)]
+pub(crate) struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    /// Starts collecting, until the guard is dropped.
+    pub(crate) fn capture() -> (Self, tracing::subscriber::DefaultGuard) {
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let guard = tracing::subscriber::set_default(
+            tracing_subscriber::fmt()
+                .with_ansi(false)
+                .with_writer(move || writer.clone())
+                .finish(),
+        );
+        (logs, guard)
+    }
+
+    /// Collects what's logged on threads that don't capture their own, for
+    /// a test whose logs come from other threads. Shared by every test that
+    /// uses it, so each should look for what only it logs.
+    pub(crate) fn global() -> Self {
+        static GLOBAL: OnceLock<Logs> = OnceLock::new();
+        GLOBAL
+            .get_or_init(|| {
+                let logs = Logs::default();
+                let writer = logs.clone();
+                tracing_subscriber::fmt()
+                    .with_ansi(false)
+                    .with_writer(move || writer.clone())
+                    .try_init()
+                    .expect("nothing else sets the global subscriber");
+                logs
+            })
+            .clone()
+    }
+
+    pub(crate) fn text(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl std::io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
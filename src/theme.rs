@@ -0,0 +1,98 @@
+use std::{path::Path, str::FromStr};
+
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+
+/// The colors the app draws with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    /// Counter values.
+    pub value: Color,
+    /// Keys in the instructions and help.
+    pub key: Color,
+    /// The spinner or percentage of an increment in progress.
+    pub progress: Color,
+    pub sparkline: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            value: Color::Yellow,
+            key: Color::Blue,
+            progress: Color::Blue,
+            sparkline: Color::Yellow,
+        }
+    }
+}
+
+impl Theme {
+    /// The theme with any 24-bit colors swapped for the nearest in the
+    /// 256-color palette's color cube, for terminals that can't show them.
+    pub fn without_truecolor(self) -> Theme {
+        Theme {
+            value: indexed(self.value),
+            key: indexed(self.key),
+            progress: indexed(self.progress),
+            sparkline: indexed(self.sparkline),
+        }
+    }
+
+    /// Reads a theme file; see [`Theme::from_str`] for the format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Theme> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read theme {}", path.display()))?;
+        text.parse()
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Bad theme {}", path.display()))
+    }
+}
+
+fn indexed(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    // The cube's six levels per channel start at index 16.
+    let level = |c: u8| ((u16::from(c) * 5 + 127) / 255) as u8;
+    Color::Indexed(16 + 36 * level(r) + 6 * level(g) + level(b))
+}
+
+/// Parses the flat subset of TOML a theme needs: `name = "color"` lines,
+/// with `#` comments and blank lines ignored. Colors are anything
+/// [`Color`] parses, such as `"light-red"`, `"#ff8800"` or `"42"`. Colors
+/// left out keep their defaults.
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `name = \"color\"`", i + 1))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            let color = value
+                .parse::<Color>()
+                .map_err(|_| format!("line {}: {:?} isn't a color", i + 1, value))?;
+            let slot = match name.trim() {
+                "value" => &mut theme.value,
+                "key" => &mut theme.key,
+                "progress" => &mut theme.progress,
+                "sparkline" => &mut theme.sparkline,
+                other => return Err(format!("line {}: no color named {:?}", i + 1, other)),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+}
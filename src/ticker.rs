@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use ractor::{Actor, ActorRef, cast, concurrency::JoinHandle};
+
+use crate::AppMessage;
+
+/// How often the app is ticked unless configured otherwise.
+pub const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sends the app a [`AppMessage::Tick`] on a fixed interval, driving
+/// anything time-based (expiring toasts and the like).
+pub struct Ticker;
+
+#[cfg_attr(feature = "recording", derive(serde::Serialize, serde::Deserialize))]
+pub enum TickerMessage {
+    Tick,
+    SetInterval(Duration),
+}
+
+impl std::fmt::Display for TickerMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TickerMessage::Tick => write!(f, "TickerMessage::Tick"),
+            TickerMessage::SetInterval(interval) => {
+                write!(f, "TickerMessage::SetInterval({:?})", interval)
+            }
+        }
+    }
+}
+
+pub struct TickerArgs {
+    pub app: ActorRef<AppMessage>,
+    pub interval: Duration,
+}
+
+pub struct TickerState {
+    app: ActorRef<AppMessage>,
+    timer: JoinHandle<()>,
+}
+
+impl Actor for Ticker {
+    type Msg = TickerMessage;
+
+    type State = TickerState;
+
+    type Arguments = TickerArgs;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ractor::ActorProcessingErr> {
+        Ok(TickerState {
+            app: args.app,
+            timer: myself.send_interval(args.interval, || TickerMessage::Tick),
+        })
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        match message {
+            TickerMessage::Tick => {
+                if let Err(e) = cast!(state.app, AppMessage::Tick) {
+                    tracing::warn!("Failed to tick app: {}", e);
+                }
+            }
+            TickerMessage::SetInterval(interval) => {
+                tracing::info!("Setting tick interval to {:?}", interval);
+                state.timer.abort();
+                state.timer = myself.send_interval(interval, || TickerMessage::Tick);
+            }
+        }
+        Ok(())
+    }
+
+    async fn post_stop(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        state: &mut Self::State,
+    ) -> Result<(), ractor::ActorProcessingErr> {
+        state.timer.abort();
+        Ok(())
+    }
+}
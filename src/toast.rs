@@ -0,0 +1,75 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// How long a toast stays up unless given a duration.
+pub const DEFAULT_TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// A transient on-screen message.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub text: String,
+    pub style: Style,
+    pub expires_at: Instant,
+}
+
+/// Toasts shown in the top-right corner, oldest first, until they expire.
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn push(&mut self, text: String, style: Style, duration: Duration) {
+        self.toasts.push_back(Toast {
+            text,
+            style,
+            expires_at: Instant::now() + duration,
+        });
+    }
+
+    /// Drops expired toasts, returning whether any were dropped.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.toasts.len();
+        self.toasts.retain(|toast| toast.expires_at > now);
+        self.toasts.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+impl Widget for &ToastQueue {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut y = area.y;
+        for toast in &self.toasts {
+            if y + 3 > area.bottom() {
+                break;
+            }
+            let width = (toast.text.width() as u16 + 4).min(area.width);
+            let toast_area = Rect {
+                x: area.right() - width,
+                y,
+                width,
+                height: 3,
+            };
+            Clear.render(toast_area, buf);
+            Paragraph::new(toast.text.as_str())
+                .centered()
+                .style(toast.style)
+                .block(Block::bordered().border_style(toast.style))
+                .render(toast_area, buf);
+            y += 3;
+        }
+    }
+}
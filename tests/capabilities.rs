@@ -0,0 +1,35 @@
+use ratatui::style::Color;
+use ratatui_ractor::{Capabilities, Theme};
+
+#[test]
+fn basic_terminals_get_only_what_they_support() {
+    let modern = Capabilities::from_env(Some("xterm-256color"), Some("truecolor"));
+    assert_eq!(modern, Capabilities::default());
+
+    let no_truecolor = Capabilities::from_env(Some("xterm-256color"), None);
+    assert!(!no_truecolor.truecolor);
+    assert!(no_truecolor.mouse && no_truecolor.focus_reporting);
+
+    let console = Capabilities::from_env(Some("linux"), None);
+    assert!(console.mouse);
+    assert!(!console.focus_reporting);
+
+    for term in [None, Some("dumb"), Some("vt100")] {
+        let basic = Capabilities::from_env(term, None);
+        assert!(!basic.mouse && !basic.focus_reporting, "{:?}", term);
+    }
+}
+
+#[test]
+fn truecolor_themes_fall_back_to_the_color_cube() {
+    let theme = Theme {
+        value: Color::Rgb(255, 136, 0),
+        key: Color::Rgb(0, 0, 0),
+        ..Theme::default()
+    };
+    let indexed = theme.without_truecolor();
+    assert_eq!(indexed.value, Color::Indexed(16 + 36 * 5 + 6 * 3));
+    assert_eq!(indexed.key, Color::Indexed(16));
+    // Named colors are left alone.
+    assert_eq!(indexed.progress, theme.progress);
+}
@@ -0,0 +1,62 @@
+use std::process::{Command, Output};
+
+/// Runs the binary with `args`, logging to a directory of its own, which is
+/// handed back so the test can see what was written there.
+fn run(name: &str, args: &[&str]) -> (Output, std::path::PathBuf) {
+    let log_dir = std::env::temp_dir().join(format!(
+        "ratatui-ractor-cli-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&log_dir);
+    let output = Command::new(env!("CARGO_BIN_EXE_ratatui-ractor"))
+        .arg(format!("--log-dir={}", log_dir.display()))
+        .args(args)
+        .output()
+        .unwrap();
+    (output, log_dir)
+}
+
+#[test]
+fn check_prints_the_settings_without_logging() {
+    let (output, log_dir) = run("check", &["--check", "--align=center"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("align:          Center"), "{}", stdout);
+    assert!(
+        !log_dir.exists(),
+        "--check wrote logs to {}",
+        log_dir.display()
+    );
+}
+
+#[test]
+fn check_fails_on_an_invalid_config() {
+    let (output, _) = run("bad-align", &["--check", "--align=sideways"]);
+    assert!(!output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("sideways"));
+
+    let (output, log_dir) = run("bad-theme", &["--check", "--theme=/nonexistent/theme"]);
+    assert!(!output.status.success(), "{:?}", output);
+    assert!(!log_dir.exists());
+}
+
+#[test]
+fn check_fails_on_an_out_of_range_bound() {
+    let (output, log_dir) = run(
+        "bad-cancel-bound",
+        &["--check", "--confirm-cancel-above=150"],
+    );
+    assert!(!output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Bad --confirm-cancel-above \"150\""),
+        "{}",
+        stderr
+    );
+    assert!(!log_dir.exists());
+
+    let (output, _) = run("bad-reset-bound", &["--check", "--confirm-reset-above=300"]);
+    assert!(!output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Bad --confirm-reset-above"));
+}
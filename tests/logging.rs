@@ -0,0 +1,74 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use ratatui_ractor::{LogConfig, Rotation};
+
+/// A directory of its own for each test, emptied first.
+fn log_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ratatui-ractor-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// The suffix of each file written to `dir`, split on `-`.
+fn suffixes(dir: &Path) -> Vec<Vec<String>> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            let suffix = name.strip_prefix("test").unwrap();
+            suffix
+                .trim_start_matches('.')
+                .split('-')
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .collect()
+}
+
+fn write_with(rotation: Rotation, name: &str) -> PathBuf {
+    let dir = log_dir(name);
+    let config = LogConfig {
+        dir: dir.clone(),
+        prefix: "test".to_string(),
+        rotation,
+    };
+    let mut writer = config.writer().unwrap();
+    writer.write_all(b"hello\n").unwrap();
+    writer.flush().unwrap();
+    dir
+}
+
+#[test]
+fn rotation_picks_how_files_are_named() {
+    // Daily files end in the date; hourly ones add the hour.
+    let daily = write_with(Rotation::Daily, "daily");
+    assert_eq!(
+        suffixes(&daily).iter().map(Vec::len).collect::<Vec<_>>(),
+        [3]
+    );
+    let hourly = write_with(Rotation::Hourly, "hourly");
+    assert_eq!(
+        suffixes(&hourly).iter().map(Vec::len).collect::<Vec<_>>(),
+        [4]
+    );
+    let never = write_with(Rotation::Never, "never");
+    assert_eq!(suffixes(&never), [Vec::<String>::new()]);
+    assert_eq!(
+        std::fs::read_to_string(never.join("test")).unwrap(),
+        "hello\n"
+    );
+    for dir in [daily, hourly, never] {
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn rotations_parse_from_their_names() {
+    assert_eq!("hourly".parse(), Ok(Rotation::Hourly));
+    assert_eq!("never".parse(), Ok(Rotation::Never));
+    assert!("weekly".parse::<Rotation>().is_err());
+}
@@ -0,0 +1,47 @@
+use ractor::{Actor, registry};
+use ratatui_ractor::{
+    AppHandle, CounterArgs, CounterHandle, CounterSupervisor,
+    names::{APP_ACTOR_NAME, COUNTER_ACTOR_NAME, namespaced},
+};
+
+#[test]
+fn handles_name_the_same_actors_as_the_constants() {
+    assert_eq!(AppHandle::NAME, APP_ACTOR_NAME);
+    assert_eq!(CounterHandle::NAME, COUNTER_ACTOR_NAME);
+}
+
+#[tokio::test]
+async fn the_supervisor_registers_its_counter_by_name() {
+    let (supervisor, handle) = Actor::spawn(None, CounterSupervisor, CounterArgs::default())
+        .await
+        .unwrap();
+    assert!(registry::where_is(COUNTER_ACTOR_NAME.to_string()).is_some());
+    supervisor.stop(None);
+    handle.await.unwrap();
+}
+
+#[test]
+fn namespaces_prefix_names() {
+    assert_eq!(namespaced(Some("left"), APP_ACTOR_NAME), "left:app");
+    assert_eq!(namespaced(None, APP_ACTOR_NAME), APP_ACTOR_NAME);
+}
+
+#[tokio::test]
+async fn namespaced_counters_register_side_by_side() {
+    let mut spawned = Vec::new();
+    for namespace in ["left", "right"] {
+        let args = CounterArgs {
+            namespace: Some(namespace.to_string()),
+            ..CounterArgs::default()
+        };
+        spawned.push(Actor::spawn(None, CounterSupervisor, args).await.unwrap());
+    }
+    let left = registry::where_is("left:counter".to_string()).unwrap();
+    let right = registry::where_is("right:counter".to_string()).unwrap();
+    assert_ne!(left.get_id(), right.get_id());
+    for (supervisor, handle) in spawned {
+        supervisor.stop(None);
+        handle.await.unwrap();
+    }
+    assert!(registry::where_is("left:counter".to_string()).is_none());
+}
@@ -0,0 +1,38 @@
+use ratatui_ractor::replay::{REPLAY_CAPACITY, ReplayLog, ReplayOp, replay};
+
+#[test]
+fn a_client_catches_up_from_where_it_left_off() {
+    let mut log = ReplayLog::default();
+    for op in [
+        ReplayOp::Update(3),
+        ReplayOp::Update(4),
+        ReplayOp::Reset,
+        ReplayOp::Update(7),
+        ReplayOp::Update(9),
+    ] {
+        log.push(op);
+    }
+
+    // Last saw the second change, so it has 4.
+    let missed = log.since(2);
+    assert_eq!(
+        missed.iter().map(|event| event.seq).collect::<Vec<_>>(),
+        [3, 4, 5]
+    );
+    assert_eq!(replay(4, &missed), 9);
+    // Nothing new for a client that's up to date.
+    assert_eq!(log.since(5), []);
+    assert_eq!(replay(9, &log.since(5)), 9);
+}
+
+#[test]
+fn changes_past_the_capacity_are_dropped_oldest_first() {
+    let mut log = ReplayLog::default();
+    for value in 0..REPLAY_CAPACITY + 5 {
+        log.push(ReplayOp::Update(value as u8));
+    }
+    let kept = log.since(0);
+    assert_eq!(kept.len(), REPLAY_CAPACITY);
+    // The gap before the first kept change shows some were missed.
+    assert_eq!(kept[0].seq, 6);
+}
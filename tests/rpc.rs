@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use ractor::{Actor, ActorProcessingErr, ActorRef, RactorErr, RpcReplyPort};
+use ratatui_ractor::call_timeout;
+
+/// Takes ten seconds to answer anything, like an app stuck in a draw.
+struct Slow;
+
+impl Actor for Slow {
+    type Msg = RpcReplyPort<()>;
+    type State = ();
+    type Arguments = ();
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        _args: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(())
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        reply: Self::Msg,
+        _state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let _ = reply.send(());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn a_slow_actor_times_out_the_call() {
+    let (actor, handle) = Actor::spawn(None, Slow, ()).await.unwrap();
+
+    let started = Instant::now();
+    let result = call_timeout!(actor, |reply| reply, Duration::from_millis(100));
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert!(matches!(result, Err(RactorErr::Timeout)), "{:?}", result);
+    actor.kill();
+    handle.await.unwrap();
+}
@@ -0,0 +1,38 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+use ratatui_ractor::snapshot::diff_buffers;
+
+#[test]
+fn diff_lists_each_differing_cell() {
+    let expected = Buffer::with_lines(["ab", "cd"]);
+    let actual = Buffer::with_lines(["ab", "xd"]);
+
+    assert_eq!(diff_buffers(&expected, &expected.clone()), None);
+    assert_eq!(
+        diff_buffers(&expected, &actual).unwrap(),
+        concat!(
+            "buffers differ in 1 cell(s):\n",
+            "  (0, 1): expected \"c\", got \"x\"\n",
+            "expected:\n",
+            "  \"ab\"\n",
+            "  \"cd\"\n",
+            "actual:\n",
+            "  \"ab\"\n",
+            "  \"xd\"\n",
+        )
+    );
+
+    let mut styled = expected.clone();
+    styled[(1, 1)].set_style(Style::new().fg(Color::Red));
+    let diff = diff_buffers(&expected, &styled).unwrap();
+    assert!(diff.starts_with("buffers differ in 1 cell(s):\n  (1, 1): \"d\" style "));
+
+    assert_eq!(
+        diff_buffers(&expected, &Buffer::empty(Rect::new(0, 0, 3, 2))).unwrap(),
+        "buffer areas differ: expected Rect { x: 0, y: 0, width: 2, height: 2 }, \
+         got Rect { x: 0, y: 0, width: 3, height: 2 }"
+    );
+}
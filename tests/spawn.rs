@@ -0,0 +1,453 @@
+use std::{
+    io,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ractor::{Actor, call};
+use ratatui::{
+    Terminal,
+    backend::{Backend, TestBackend, WindowSize},
+    buffer::Cell,
+    layout::{Position, Size},
+};
+use ratatui_ractor::{
+    ActorRefs, AppHandle, AppMessage, AuditEntry, AuditMessage, Config, CounterArgs, CounterHandle,
+    CounterSupervisor, MirrorMessage, demo::DEMO_WORK_DURATION, spawn_all,
+};
+
+/// A [`TestBackend`] that can still be read once the app owns it.
+#[derive(Debug, Clone)]
+struct SharedBackend {
+    backend: Arc<Mutex<TestBackend>>,
+    /// Each draw ends with a flush.
+    flushes: Arc<AtomicUsize>,
+}
+
+impl SharedBackend {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            backend: Arc::new(Mutex::new(TestBackend::new(width, height))),
+            flushes: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn draws(&self) -> usize {
+        self.flushes.load(Ordering::Relaxed)
+    }
+
+    /// What's on screen, a line per row.
+    fn screen(&self) -> String {
+        let backend = self.backend.lock().unwrap();
+        let buffer = backend.buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Backend for SharedBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.backend.lock().unwrap().draw(content)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.backend.lock().unwrap().hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.backend.lock().unwrap().show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.backend.lock().unwrap().get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.backend.lock().unwrap().set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.backend.lock().unwrap().clear()
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.backend.lock().unwrap().size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.backend.lock().unwrap().window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        self.backend.lock().unwrap().flush()
+    }
+}
+
+/// Everything spawned in `namespace` against an 80x20 test backend, with
+/// every increment taking `work`.
+async fn spawn_test(namespace: &str, work: Duration) -> (ActorRefs, SharedBackend) {
+    let backend = SharedBackend::new(80, 20);
+    let refs = spawn_all(
+        Terminal::new(backend.clone()).unwrap(),
+        Config {
+            counter: CounterArgs {
+                work_duration: work,
+                ..CounterArgs::default()
+            },
+            namespace: Some(namespace.to_string()),
+            ..Config::default()
+        },
+    )
+    .await
+    .unwrap();
+    (refs, backend)
+}
+
+/// Stops everything in the order `main` does.
+async fn shut_down(refs: ActorRefs) {
+    let ActorRefs {
+        app,
+        app_handle,
+        counter,
+        counter_handle,
+        ticker,
+        ticker_handle,
+        audit,
+        audit_handle,
+        mirror,
+        mirror_handle,
+    } = refs;
+    ticker.stop(None);
+    ticker_handle.await.unwrap();
+    counter.stop(None);
+    counter_handle.await.unwrap();
+    app.stop(None);
+    app_handle.await.unwrap();
+    audit.stop(None);
+    mirror.stop(None);
+    audit_handle.await.unwrap();
+    mirror_handle.await.unwrap();
+}
+
+/// Waits for the app's value to come back as `value`.
+async fn wait_for_value(app: &AppHandle, value: u8) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while app.get_counter().await.unwrap() != value {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("never got to {}", value));
+}
+
+#[tokio::test]
+async fn everything_runs_against_a_test_backend() {
+    let (refs, backend) = spawn_test("test-backend", Duration::from_millis(100)).await;
+    let app = AppHandle::new(refs.app.clone());
+
+    app.set_value(7).await.unwrap();
+    // The value goes by way of the counter, and may wait out the redraw
+    // window before it's drawn.
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while !backend.screen().contains("Value: 7") {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("never drew the value:\n{}", backend.screen()));
+    assert!(backend.screen().contains("Counter App Tutorial"));
+
+    app.quit().await.unwrap();
+    assert!(app.exit().await.unwrap());
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn end_and_home_jump_to_the_bounds() {
+    let (refs, _backend) = spawn_test("jump", Duration::from_secs(10)).await;
+    let app = AppHandle::new(refs.app.clone());
+    let press = |code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // A slow increment that the jump shouldn't wait on.
+    press(KeyCode::Right).await.unwrap();
+    press(KeyCode::End).await.unwrap();
+    wait_for_value(&app, u8::MAX).await;
+    press(KeyCode::Home).await.unwrap();
+    wait_for_value(&app, u8::MIN).await;
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn nothing_is_drawn_once_everything_has_stopped() {
+    let (refs, backend) = spawn_test("shutdown", Duration::from_millis(100)).await;
+    let app = AppHandle::new(refs.app.clone());
+    app.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE))
+        .await
+        .unwrap();
+    // Stopping mid-task, which would otherwise finish and update the app
+    // after the terminal is restored.
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    shut_down(refs).await;
+    let drawn = backend.draws();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(backend.draws(), drawn);
+    assert!(
+        !backend.screen().contains("Value: 1"),
+        "{}",
+        backend.screen()
+    );
+}
+
+#[tokio::test]
+async fn spawning_twice_in_one_namespace_names_the_clash() {
+    let (refs, _backend) = spawn_test("twice", Duration::from_millis(100)).await;
+
+    let Err(err) = spawn_all(
+        Terminal::new(SharedBackend::new(80, 20)).unwrap(),
+        Config {
+            namespace: Some("twice".to_string()),
+            ..Config::default()
+        },
+    )
+    .await
+    else {
+        panic!("spawned a second set under the same names");
+    };
+    let message = format!("{:#}", err);
+    assert!(
+        message.starts_with("Failed to spawn the audit actor"),
+        "{}",
+        message
+    );
+    assert!(message.contains("already registered"), "{}", message);
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn resetting_from_the_palette_zeroes_the_counter() {
+    let (refs, _backend) = spawn_test("palette", Duration::from_millis(100)).await;
+    let app = AppHandle::new(refs.app.clone());
+    app.set_value(5).await.unwrap();
+    wait_for_value(&app, 5).await;
+
+    let keys = std::iter::once(KeyCode::Char(':'))
+        .chain("reset".chars().map(KeyCode::Char))
+        .chain([KeyCode::Enter]);
+    for code in keys {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .await
+            .unwrap();
+    }
+    wait_for_value(&app, 0).await;
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn a_labeled_increment_is_audited() {
+    let (refs, _backend) = spawn_test("audit", Duration::from_millis(100)).await;
+    let app = AppHandle::new(refs.app.clone());
+    CounterHandle::new(refs.counter.clone())
+        .increment_with_metadata(3, "nudge".to_string(), "test".to_string())
+        .await
+        .unwrap();
+    wait_for_value(&app, 4).await;
+
+    let entries = call!(refs.audit, AuditMessage::GetEntries).unwrap();
+    assert_eq!(
+        entries,
+        [AuditEntry {
+            value: 4,
+            label: "nudge".to_string(),
+            source: "test".to_string(),
+        }]
+    );
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn the_mirror_follows_the_app() {
+    let (refs, _backend) = spawn_test("mirror", Duration::from_millis(100)).await;
+    let app = AppHandle::new(refs.app.clone());
+    for value in 1..=2 {
+        app.increment().await.unwrap();
+        wait_for_value(&app, value).await;
+    }
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while call!(refs.mirror, MirrorMessage::GetValue).unwrap() != 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the mirror never caught up");
+    shut_down(refs).await;
+}
+
+/// Waits for `text` to be drawn.
+async fn wait_for_screen(backend: &SharedBackend, text: &str) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while !backend.screen().contains(text) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("never drew {:?}:\n{}", text, backend.screen()));
+}
+
+#[tokio::test]
+async fn named_counters_count_side_by_side() {
+    let work = Duration::from_millis(300);
+    let (refs, backend) = spawn_test("named", work).await;
+    let app = AppHandle::new(refs.app.clone());
+    let mut named = Vec::new();
+    for name in ["a", "b"] {
+        let (counter, handle) = Actor::spawn(
+            None,
+            CounterSupervisor,
+            CounterArgs {
+                work_duration: work,
+                name: Some(name.to_string()),
+                namespace: Some(format!("named-{}", name)),
+                ..CounterArgs::default()
+            },
+        )
+        .await
+        .unwrap();
+        refs.app
+            .cast(AppMessage::RegisterCounter(
+                name.to_string(),
+                counter.clone(),
+            ))
+            .unwrap();
+        named.push((counter, handle));
+    }
+    wait_for_screen(&backend, "b: 0").await;
+
+    // Tab moves focus to each in turn, and each starts its own increment
+    // while the other's is still running.
+    let started = Instant::now();
+    for code in [KeyCode::Tab, KeyCode::Right, KeyCode::Tab, KeyCode::Right] {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .await
+            .unwrap();
+    }
+    wait_for_screen(&backend, "a: 1").await;
+    wait_for_screen(&backend, "b: 1").await;
+    assert!(started.elapsed() < work * 2, "{:?}", started.elapsed());
+    assert_eq!(app.get_counter().await.unwrap(), 0);
+
+    for (counter, handle) in named {
+        counter.stop(None);
+        handle.await.unwrap();
+    }
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn right_waits_on_the_counter_unless_in_sync_mode() {
+    let (refs, _backend) = spawn_test("sync", Duration::from_millis(300)).await;
+    let app = AppHandle::new(refs.app.clone());
+    let press = |code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    press(KeyCode::Char('s')).await.unwrap();
+    press(KeyCode::Right).await.unwrap();
+    assert_eq!(app.get_counter().await.unwrap(), 1);
+
+    // Back to going through the counter, which answers once its work is done.
+    press(KeyCode::Char('s')).await.unwrap();
+    press(KeyCode::Right).await.unwrap();
+    assert_eq!(app.get_counter().await.unwrap(), 1);
+    wait_for_value(&app, 2).await;
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn the_demo_plays_through_to_its_end() {
+    let backend = SharedBackend::new(80, 20);
+    let refs = spawn_all(
+        Terminal::new(backend.clone()).unwrap(),
+        Config {
+            counter: CounterArgs {
+                work_duration: DEMO_WORK_DURATION,
+                ..CounterArgs::default()
+            },
+            demo: true,
+            namespace: Some("demo".to_string()),
+            ..Config::default()
+        },
+    )
+    .await
+    .unwrap();
+    let app = AppHandle::new(refs.app.clone());
+    wait_for_screen(&backend, "[auto]").await;
+
+    // The demo takes a little over ten seconds, and ends by quitting.
+    let stopped = app.watch_exit().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(20), app.wait_for_exit(stopped))
+        .await
+        .expect("the demo never quit")
+        .unwrap();
+    let snapshot = app.snapshot().await.unwrap();
+    assert_eq!(snapshot.counter, 1);
+    assert_eq!(snapshot.history, [1, 2, 3, 0, 1, 2, 1]);
+    wait_for_screen(&backend, "Value: 1").await;
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn left_waits_on_the_counter_to_decrement() {
+    let (refs, _backend) = spawn_test("decrement", Duration::from_millis(300)).await;
+    let app = AppHandle::new(refs.app.clone());
+    app.set_value(5).await.unwrap();
+    wait_for_value(&app, 5).await;
+
+    app.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE))
+        .await
+        .unwrap();
+    // Handled, and the counter's been asked, but it hasn't answered.
+    assert_eq!(app.get_counter().await.unwrap(), 5);
+    wait_for_value(&app, 4).await;
+    let events = app.events_since(0).await.unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.op.value())
+            .collect::<Vec<_>>(),
+        [5, 4]
+    );
+    shut_down(refs).await;
+}
+
+#[tokio::test]
+async fn the_last_frame_shows_the_value_at_quitting() {
+    let (refs, backend) = spawn_test("quit", Duration::from_millis(300)).await;
+    let app = AppHandle::new(refs.app.clone());
+    let press = |code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    app.set_value(3).await.unwrap();
+    wait_for_screen(&backend, "Value: 3").await;
+
+    // An increment still working as the app quits, which mustn't be shown.
+    press(KeyCode::Right).await.unwrap();
+    // Quitting with unsaved changes asks first.
+    press(KeyCode::Char('q')).await.unwrap();
+    press(KeyCode::Char('y')).await.unwrap();
+    assert!(app.exit().await.unwrap());
+    shut_down(refs).await;
+    let screen = backend.screen();
+    assert!(screen.contains("Value: 3"), "{}", screen);
+    assert!(!screen.contains("Unsaved"), "{}", screen);
+}